@@ -0,0 +1,356 @@
+//! Procedural macros for `loomy`.
+//!
+//! Use these through their re-exports in `loomy` rather than depending on this
+//! crate directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{quote, quote_spanned};
+use syn::{
+    parse::{Parse, ParseStream, Parser},
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Block, Error, Expr, Ident, ItemFn, Local, Meta, Pat, Stmt, Token,
+};
+
+/// Define a test whose body is run with `loomy::model`.
+///
+/// See `loomy::test` for the accepted arguments.
+#[proc_macro_attribute]
+pub fn test(args: TokenStream, item: TokenStream) -> TokenStream {
+    expand_test(args.into(), item.into())
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_test(args: TokenStream2, item: TokenStream2) -> syn::Result<TokenStream2> {
+    let args = Punctuated::<Meta, Token![,]>::parse_terminated.parse2(args)?;
+    let item: ItemFn = syn::parse2(item)?;
+
+    let sig = &item.sig;
+
+    if let Some(asyncness) = &sig.asyncness {
+        return Err(Error::new(asyncness.span, "loomy tests cannot be `async`"));
+    }
+
+    if !sig.inputs.is_empty() {
+        return Err(Error::new(
+            sig.inputs.span(),
+            "loomy tests cannot take arguments",
+        ));
+    }
+
+    if let syn::ReturnType::Type(..) = sig.output {
+        return Err(Error::new(
+            sig.output.span(),
+            "loomy tests cannot return a value",
+        ));
+    }
+
+    let mut config = Vec::new();
+    let mut ignore_in_std = false;
+
+    for arg in args {
+        let name = arg.path().get_ident().map(|i| i.to_string());
+
+        match (name.as_deref(), &arg) {
+            (Some("ignore_in_std"), Meta::Path(_)) => ignore_in_std = true,
+            (
+                Some(field @ ("preemption_bound" | "max_permutations" | "pct_depth")),
+                Meta::NameValue(nv),
+            ) => {
+                config.push(field_assign(field, nv.value.span(), wrap_some(&nv.value)));
+            }
+            (Some(field @ ("max_branches" | "max_threads")), Meta::NameValue(nv)) => {
+                let value = &nv.value;
+                config.push(field_assign(field, value.span(), quote!(#value)));
+            }
+            (Some("max_duration"), Meta::NameValue(nv)) => {
+                let value = &nv.value;
+                let value = quote_spanned!(value.span()=>
+                    ::core::option::Option::Some(::std::time::Duration::from_secs(#value))
+                );
+                config.push(field_assign("max_duration", nv.value.span(), value));
+            }
+            (Some("std_timeout"), Meta::NameValue(nv)) => {
+                let value = &nv.value;
+                let value = quote_spanned!(value.span()=>
+                    ::core::option::Option::Some(::std::time::Duration::from_secs(#value))
+                );
+                config.push(field_assign("timeout", nv.value.span(), value));
+            }
+            (Some("checkpoint_file"), Meta::NameValue(nv)) => {
+                let value = &nv.value;
+                config.push(quote_spanned!(value.span()=> builder.checkpoint_file(#value);));
+            }
+            (Some("std_iterations"), Meta::NameValue(nv)) => {
+                let value = &nv.value;
+                config.push(field_assign("iterations", value.span(), quote!(#value)));
+            }
+            _ => {
+                return Err(Error::new(
+                    arg.span(),
+                    "unknown argument, expected one of `preemption_bound`, `max_branches`, \
+                     `max_threads`, `max_permutations`, `max_duration`, `checkpoint_file`, \
+                     `std_iterations`, `std_timeout`, `pct_depth` or `ignore_in_std`",
+                ));
+            }
+        }
+    }
+
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = item;
+
+    let test = quote! {
+        #[::core::prelude::v1::test]
+        #(#attrs)*
+        #vis #sig {
+            #[allow(unused_mut)]
+            let mut builder = ::loomy::model::Builder::new();
+            #(#config)*
+            builder.check(|| #block);
+        }
+    };
+
+    if ignore_in_std {
+        Ok(quote!(::loomy::__ignore_in_std! { #test }))
+    } else {
+        Ok(test)
+    }
+}
+
+fn field_assign(field: &str, span: Span, value: TokenStream2) -> TokenStream2 {
+    let field = syn::Ident::new(field, span);
+    quote_spanned!(span=> builder.#field = #value;)
+}
+
+fn wrap_some(value: &Expr) -> TokenStream2 {
+    quote_spanned!(value.span()=> ::core::option::Option::Some(#value))
+}
+
+/// Run a memory-model litmus test with `loomy::model`.
+///
+/// See `loomy::litmus!` for the syntax.
+#[proc_macro]
+pub fn litmus(input: TokenStream) -> TokenStream {
+    syn::parse_macro_input!(input as Litmus).expand().into()
+}
+
+mod kw {
+    syn::custom_keyword!(shared);
+    syn::custom_keyword!(thread);
+    syn::custom_keyword!(allowed);
+    syn::custom_keyword!(forbidden);
+}
+
+struct Litmus {
+    shared: Vec<(Ident, Expr)>,
+    threads: Vec<Thread>,
+    allowed: Vec<Expr>,
+    forbidden: Vec<Expr>,
+}
+
+struct Thread {
+    stmts: Vec<Stmt>,
+    registers: Vec<Ident>,
+}
+
+impl Parse for Litmus {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut litmus = Litmus {
+            shared: Vec::new(),
+            threads: Vec::new(),
+            allowed: Vec::new(),
+            forbidden: Vec::new(),
+        };
+
+        while !input.is_empty() {
+            let lookahead = input.lookahead1();
+
+            if lookahead.peek(kw::shared) {
+                input.parse::<kw::shared>()?;
+
+                loop {
+                    let name: Ident = input.parse()?;
+
+                    let init = if input.parse::<Option<Token![=]>>()?.is_some() {
+                        input.parse()?
+                    } else {
+                        syn::parse_quote!(0)
+                    };
+
+                    litmus.shared.push((name, init));
+
+                    if input.parse::<Option<Token![,]>>()?.is_none() {
+                        break;
+                    }
+                }
+
+                input.parse::<Token![;]>()?;
+            } else if lookahead.peek(kw::thread) {
+                input.parse::<kw::thread>()?;
+                litmus.threads.push(Thread::new(input.parse()?)?);
+            } else if lookahead.peek(kw::allowed) {
+                input.parse::<kw::allowed>()?;
+                litmus.allowed.push(input.parse()?);
+                input.parse::<Token![;]>()?;
+            } else if lookahead.peek(kw::forbidden) {
+                input.parse::<kw::forbidden>()?;
+                litmus.forbidden.push(input.parse()?);
+                input.parse::<Token![;]>()?;
+            } else {
+                return Err(lookahead.error());
+            }
+        }
+
+        if litmus.threads.is_empty() {
+            return Err(input.error("litmus tests need at least one `thread`"));
+        }
+
+        let mut registers = Vec::<&Ident>::new();
+
+        for register in litmus.threads.iter().flat_map(|t| &t.registers) {
+            if registers.contains(&register) {
+                return Err(Error::new(
+                    register.span(),
+                    format!("register `{register}` is declared more than once"),
+                ));
+            }
+
+            if litmus.shared.iter().any(|(name, _)| name == register) {
+                return Err(Error::new(
+                    register.span(),
+                    format!("register `{register}` shadows a shared location"),
+                ));
+            }
+
+            registers.push(register);
+        }
+
+        Ok(litmus)
+    }
+}
+
+impl Thread {
+    /// Every top-level `let` binding a single name declares a register.
+    fn new(block: Block) -> syn::Result<Thread> {
+        let mut registers = Vec::new();
+
+        for stmt in &block.stmts {
+            if let Stmt::Local(Local { pat, .. }) = stmt {
+                let pat = match pat {
+                    Pat::Type(ty) => &*ty.pat,
+                    pat => pat,
+                };
+
+                match pat {
+                    Pat::Ident(ident) => registers.push(ident.ident.clone()),
+                    pat => {
+                        return Err(Error::new(
+                            pat.span(),
+                            "registers must be bound to a single name",
+                        ))
+                    }
+                }
+            }
+        }
+
+        Ok(Thread {
+            stmts: block.stmts,
+            registers,
+        })
+    }
+
+    fn expand(&self) -> TokenStream2 {
+        let registers = &self.registers;
+
+        // A trailing expression would otherwise be followed by the registers.
+        let stmts = self.stmts.iter().map(|stmt| match stmt {
+            Stmt::Expr(expr, None) => quote!(#expr;),
+            stmt => quote!(#stmt),
+        });
+
+        quote!({
+            #(#stmts)*
+            ::std::vec::Vec::<usize>::from([#(#registers,)*])
+        })
+    }
+}
+
+impl Litmus {
+    fn expand(&self) -> TokenStream2 {
+        let shared = self.shared.iter().map(|(name, _)| name);
+        let init = self.shared.iter().map(|(_, init)| init);
+        let finals = self.shared.iter().map(|(name, _)| name);
+
+        let len = self.threads.len();
+        let indices = 0..len;
+        let threads = self.threads.iter().map(Thread::expand);
+
+        // Every register, along with its thread and position within it.
+        let bindings = self.threads.iter().enumerate().flat_map(|(i, thread)| {
+            thread
+                .registers
+                .iter()
+                .enumerate()
+                .map(move |(j, register)| quote!(let #register = __registers[#i][#j];))
+        });
+
+        let registers = self
+            .threads
+            .iter()
+            .flat_map(|thread| &thread.registers)
+            .collect::<Vec<_>>();
+
+        let names = registers.iter().map(|r| r.to_string());
+
+        let forbidden = &self.forbidden;
+        let forbidden_text = forbidden.iter().map(|c| quote!(#c).to_string());
+
+        let allowed = &self.allowed;
+        let allowed_text = allowed.iter().map(|c| quote!(#c).to_string());
+
+        quote! {{
+            let __outcomes = ::loomy::outcomes(|| {
+                #[allow(unused_imports)]
+                use ::loomy::sync::atomic::{fence, AtomicUsize, Ordering::*};
+
+                #(let #shared = AtomicUsize::new(#init);)*
+
+                let __registers = ::loomy::__run_threads(#len, |__thread| match __thread {
+                    #(#indices => #threads,)*
+                    _ => ::core::unreachable!(),
+                });
+
+                #(#bindings)*
+
+                #(
+                    #[allow(unused_variables)]
+                    let #finals = #finals.into_inner();
+                )*
+
+                let __outcome = ::loomy::litmus::Outcome::__new(::std::vec![
+                    #((#names, #registers),)*
+                ]);
+
+                #(
+                    if #forbidden {
+                        ::loomy::litmus::__forbidden(#forbidden_text, &__outcome);
+                    }
+                )*
+
+                ::loomy::observe(
+                    ::loomy::litmus::__ALLOWED,
+                    ::std::vec::Vec::<bool>::from([#(#allowed,)*]),
+                );
+                ::loomy::observe(::loomy::litmus::__OUTCOMES, __outcome);
+            });
+
+            ::loomy::litmus::Report::__new(&__outcomes, &[#(#allowed_text,)*])
+        }}
+    }
+}
@@ -0,0 +1,50 @@
+//! The model the current thread takes part in.
+//!
+//! Bookkeeping which lives outside of the model, such as observations and
+//! tracked values, is keyed by the ids held in the current thread's context.
+//! Under `loom` and `shuttle`, every thread of a model runs on the thread
+//! running the model, so its context covers the whole model. Under `std`,
+//! `loomy::thread` carries the context into the threads it spawns.
+
+use std::cell::RefCell;
+
+thread_local! {
+    static CONTEXT: RefCell<Context> = const { RefCell::new(Context::NONE) };
+}
+
+pub(crate) struct Context {
+    /// The `outcomes` session to record observations into.
+    pub(crate) session: Option<u64>,
+
+    /// The execution to register tracked values with.
+    pub(crate) execution: Option<u64>,
+
+    /// This thread's stream of the stress iteration it takes part in.
+    #[cfg(not(any(loom, feature = "shuttle")))]
+    pub(crate) stress: Option<crate::imp::stress::Stream>,
+
+    /// The watched execution to list this thread's state under if it hangs.
+    #[cfg(not(any(loom, feature = "shuttle")))]
+    pub(crate) watchdog: Option<u64>,
+}
+
+impl Context {
+    pub(crate) const NONE: Context = Context {
+        session: None,
+        execution: None,
+        #[cfg(not(any(loom, feature = "shuttle")))]
+        stress: None,
+        #[cfg(not(any(loom, feature = "shuttle")))]
+        watchdog: None,
+    };
+}
+
+/// Run `f` with the current thread's context.
+pub(crate) fn with<R>(f: impl FnOnce(&Context) -> R) -> R {
+    CONTEXT.with(|context| f(&context.borrow()))
+}
+
+/// Run `f` with mutable access to the current thread's context.
+pub(crate) fn update<R>(f: impl FnOnce(&mut Context) -> R) -> R {
+    CONTEXT.with(|context| f(&mut context.borrow_mut()))
+}
@@ -0,0 +1,103 @@
+pub use std::alloc::Layout;
+
+#[cfg(not(feature = "checked"))]
+pub use std::alloc::{alloc, alloc_zeroed, dealloc, realloc};
+
+#[cfg(feature = "checked")]
+pub use self::checked::{alloc, alloc_zeroed, dealloc, realloc};
+
+/// A value tracked for leaks, mirroring `loom::alloc::Track`.
+///
+/// Only `loom` checks for leaks; elsewhere this is a plain wrapper.
+#[derive(Debug)]
+pub struct Track<T>(T);
+
+impl<T> Track<T> {
+    #[inline(always)]
+    pub fn new(value: T) -> Track<T> {
+        Track(value)
+    }
+
+    #[inline(always)]
+    pub fn get_ref(&self) -> &T {
+        &self.0
+    }
+
+    #[inline(always)]
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+/// Allocation functions accounting for every allocation, so that a model
+/// execution fails on leaks, double deallocations and mismatched layouts.
+#[cfg(feature = "checked")]
+mod checked {
+    use std::alloc::Layout;
+    use std::panic::Location;
+
+    use crate::tracked::{register_alloc, release_alloc};
+
+    /// Allocate memory, accounting for the allocation.
+    ///
+    /// # Safety
+    ///
+    /// See `std::alloc::alloc`.
+    #[track_caller]
+    pub unsafe fn alloc(layout: Layout) -> *mut u8 {
+        let ptr = std::alloc::alloc(layout);
+        register_alloc(ptr, layout, Location::caller());
+        ptr
+    }
+
+    /// Allocate zeroed memory, accounting for the allocation.
+    ///
+    /// # Safety
+    ///
+    /// See `std::alloc::alloc_zeroed`.
+    #[track_caller]
+    pub unsafe fn alloc_zeroed(layout: Layout) -> *mut u8 {
+        let ptr = std::alloc::alloc_zeroed(layout);
+        register_alloc(ptr, layout, Location::caller());
+        ptr
+    }
+
+    /// Deallocate memory, checking that it is allocated with `layout`.
+    ///
+    /// # Safety
+    ///
+    /// See `std::alloc::dealloc`.
+    #[track_caller]
+    pub unsafe fn dealloc(ptr: *mut u8, layout: Layout) {
+        release_alloc(ptr, layout, Location::caller());
+        std::alloc::dealloc(ptr, layout)
+    }
+
+    /// Reallocate memory, checking that it is allocated with `layout`.
+    ///
+    /// # Safety
+    ///
+    /// See `std::alloc::realloc`.
+    #[track_caller]
+    pub unsafe fn realloc(ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let site = Location::caller();
+        release_alloc(ptr, layout, site);
+
+        let new = std::alloc::realloc(ptr, layout, new_size);
+
+        // On failure, the original allocation is left untouched.
+        if new.is_null() {
+            register_alloc(ptr, layout, site);
+        } else {
+            let layout = Layout::from_size_align_unchecked(new_size, layout.align());
+            register_alloc(new, layout, site);
+        }
+
+        new
+    }
+}
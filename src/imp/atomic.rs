@@ -0,0 +1,224 @@
+//! Atomics wrapping the backend's own, exposing the same API as loom's.
+//!
+//! Every access goes through [`stress::yield_point`] first, so that stress runs
+//! can perturb the interleaving of threads. Outside of a stress run this is a
+//! single relaxed load.
+//!
+//! Accesses are `#[track_caller]`, so panics and backend reports point at the
+//! caller rather than into loomy.
+
+pub use super::raw_atomic::{fence, Ordering};
+
+use super::{raw_atomic as raw, stress};
+
+// The backend panics on invalid orderings too, but `core`'s atomics are not
+// `#[track_caller]`, so check here to blame the caller instead.
+
+#[track_caller]
+#[inline(always)]
+fn check_load(order: Ordering) {
+    match order {
+        Ordering::Release => panic!("there is no such thing as a release load"),
+        Ordering::AcqRel => panic!("there is no such thing as an acquire-release load"),
+        _ => {}
+    }
+}
+
+#[track_caller]
+#[inline(always)]
+fn check_store(order: Ordering) {
+    match order {
+        Ordering::Acquire => panic!("there is no such thing as an acquire store"),
+        Ordering::AcqRel => panic!("there is no such thing as an acquire-release store"),
+        _ => {}
+    }
+}
+
+#[track_caller]
+#[inline(always)]
+fn check_failure(order: Ordering) {
+    match order {
+        Ordering::Release => panic!("there is no such thing as a release failure ordering"),
+        Ordering::AcqRel => {
+            panic!("there is no such thing as an acquire-release failure ordering")
+        }
+        _ => {}
+    }
+}
+
+macro_rules! atomic {
+    ($name:ident, $t:ty $(, <$param:ident>)?) => {
+        #[repr(transparent)]
+        pub struct $name$(<$param>)?(raw::$name$(<$param>)?);
+
+        impl$(<$param>)? $name$(<$param>)? {
+            #[inline(always)]
+            pub const fn new(v: $t) -> Self {
+                Self(raw::$name::new(v))
+            }
+
+            /// Load the value without any synchronization.
+            ///
+            /// # Safety
+            ///
+            /// There must be no concurrent stores to this atomic.
+            #[track_caller]
+            #[inline(always)]
+            pub unsafe fn unsync_load(&self) -> $t {
+                self.0.load(Ordering::Relaxed)
+            }
+
+            #[inline(always)]
+            pub fn into_inner(self) -> $t {
+                self.0.into_inner()
+            }
+
+            #[track_caller]
+            #[inline(always)]
+            pub fn load(&self, order: Ordering) -> $t {
+                check_load(order);
+                stress::yield_point();
+                self.0.load(order)
+            }
+
+            #[track_caller]
+            #[inline(always)]
+            pub fn store(&self, val: $t, order: Ordering) {
+                check_store(order);
+                stress::yield_point();
+                self.0.store(val, order)
+            }
+
+            #[track_caller]
+            #[inline(always)]
+            pub fn swap(&self, val: $t, order: Ordering) -> $t {
+                stress::yield_point();
+                self.0.swap(val, order)
+            }
+
+            #[track_caller]
+            #[inline(always)]
+            pub fn compare_exchange(
+                &self,
+                current: $t,
+                new: $t,
+                success: Ordering,
+                failure: Ordering,
+            ) -> Result<$t, $t> {
+                check_failure(failure);
+                stress::yield_point();
+                self.0.compare_exchange(current, new, success, failure)
+            }
+
+            #[track_caller]
+            #[inline(always)]
+            pub fn compare_exchange_weak(
+                &self,
+                current: $t,
+                new: $t,
+                success: Ordering,
+                failure: Ordering,
+            ) -> Result<$t, $t> {
+                check_failure(failure);
+                stress::yield_point();
+                self.0.compare_exchange_weak(current, new, success, failure)
+            }
+
+            #[track_caller]
+            #[inline(always)]
+            pub fn fetch_update<F>(
+                &self,
+                set_order: Ordering,
+                fetch_order: Ordering,
+                f: F,
+            ) -> Result<$t, $t>
+            where
+                F: FnMut($t) -> Option<$t>,
+            {
+                check_failure(fetch_order);
+                stress::yield_point();
+                self.0.fetch_update(set_order, fetch_order, f)
+            }
+        }
+
+        impl$(<$param>)? From<$t> for $name$(<$param>)? {
+            #[inline(always)]
+            fn from(v: $t) -> Self {
+                Self::new(v)
+            }
+        }
+
+        impl$(<$param>)? Default for $name$(<$param>)? {
+            #[inline(always)]
+            fn default() -> Self {
+                Self(Default::default())
+            }
+        }
+
+        impl$(<$param>)? std::fmt::Debug for $name$(<$param>)? {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                std::fmt::Debug::fmt(&self.0, f)
+            }
+        }
+    };
+}
+
+// `loom::sync::atomic::AtomicBool` has no `with_mut`.
+macro_rules! with_mut {
+    ($name:ident, $t:ty $(, <$param:ident>)?) => {
+        impl$(<$param>)? $name$(<$param>)? {
+            #[inline(always)]
+            pub fn with_mut<R>(&mut self, f: impl FnOnce(&mut $t) -> R) -> R {
+                f(self.0.get_mut())
+            }
+        }
+    };
+}
+
+macro_rules! fetch {
+    ($name:ident, $t:ty, $($method:ident)*) => {
+        impl $name {
+            $(
+                #[track_caller]
+                #[inline(always)]
+                pub fn $method(&self, val: $t, order: Ordering) -> $t {
+                    stress::yield_point();
+                    self.0.$method(val, order)
+                }
+            )*
+        }
+    };
+}
+
+macro_rules! atomic_int {
+    ($($name:ident, $t:ty;)*) => {
+        $(
+            atomic!($name, $t);
+            with_mut!($name, $t);
+            fetch!(
+                $name,
+                $t,
+                fetch_add fetch_sub fetch_and fetch_nand fetch_or fetch_xor fetch_max fetch_min
+            );
+        )*
+    };
+}
+
+atomic!(AtomicBool, bool);
+fetch!(AtomicBool, bool, fetch_and fetch_nand fetch_or fetch_xor);
+
+atomic!(AtomicPtr, *mut T, <T>);
+with_mut!(AtomicPtr, *mut T, <T>);
+
+atomic_int! {
+    AtomicI8, i8;
+    AtomicI16, i16;
+    AtomicI32, i32;
+    AtomicI64, i64;
+    AtomicIsize, isize;
+    AtomicU8, u8;
+    AtomicU16, u16;
+    AtomicU32, u32;
+    AtomicU64, u64;
+    AtomicUsize, usize;
+}
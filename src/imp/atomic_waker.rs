@@ -0,0 +1,69 @@
+//! `AtomicWaker` built on the backend's `Mutex`, mirroring
+//! `loom::future::AtomicWaker`.
+
+use std::fmt;
+use std::sync::PoisonError;
+use std::task::Waker;
+
+use super::sync::Mutex;
+
+/// A synchronization primitive for task wakeup, mirroring
+/// `loom::future::AtomicWaker`.
+pub struct AtomicWaker {
+    waker: Mutex<Option<Waker>>,
+}
+
+impl AtomicWaker {
+    /// Create a new instance of `AtomicWaker`.
+    pub fn new() -> AtomicWaker {
+        AtomicWaker {
+            waker: Mutex::new(None),
+        }
+    }
+
+    /// Register the current task to be notified on calls to `wake`.
+    pub fn register(&self, waker: Waker) {
+        *self.lock() = Some(waker);
+    }
+
+    /// Register the current task to be woken without consuming the value.
+    pub fn register_by_ref(&self, waker: &Waker) {
+        let mut slot = self.lock();
+
+        match &*slot {
+            Some(current) if current.will_wake(waker) => {}
+            _ => *slot = Some(waker.clone()),
+        }
+    }
+
+    /// Notify the task that last called `register`.
+    pub fn wake(&self) {
+        if let Some(waker) = self.take_waker() {
+            waker.wake();
+        }
+    }
+
+    /// Take the `Waker` out of the `AtomicWaker`, with the intention that the
+    /// caller will wake the task later.
+    pub fn take_waker(&self) -> Option<Waker> {
+        self.lock().take()
+    }
+
+    fn lock(&self) -> super::sync::MutexGuard<'_, Option<Waker>> {
+        // Wakers are only ever replaced whole, so a panic while the lock is
+        // held cannot leave the slot inconsistent.
+        self.waker.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Default for AtomicWaker {
+    fn default() -> AtomicWaker {
+        AtomicWaker::new()
+    }
+}
+
+impl fmt::Debug for AtomicWaker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AtomicWaker").finish_non_exhaustive()
+    }
+}
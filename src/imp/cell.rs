@@ -0,0 +1,117 @@
+pub use std::cell::Cell;
+
+#[cfg(feature = "checked")]
+use super::checked::Checker;
+use super::stress;
+
+#[derive(Debug, Default)]
+pub struct UnsafeCell<T> {
+    data: std::cell::UnsafeCell<T>,
+    #[cfg(feature = "checked")]
+    checker: Checker,
+}
+
+impl<T> From<T> for UnsafeCell<T> {
+    #[inline(always)]
+    fn from(t: T) -> Self {
+        Self::new(t)
+    }
+}
+
+impl<T> UnsafeCell<T> {
+    #[inline(always)]
+    pub fn new(data: T) -> UnsafeCell<T> {
+        UnsafeCell {
+            data: std::cell::UnsafeCell::new(data),
+            #[cfg(feature = "checked")]
+            checker: Checker::default(),
+        }
+    }
+
+    #[track_caller]
+    #[inline(always)]
+    pub fn with<R>(&self, f: impl FnOnce(*const T) -> R) -> R {
+        #[cfg(feature = "checked")]
+        let _access = self.checker.read();
+
+        stress::yield_point();
+        f(self.data.get())
+    }
+
+    #[track_caller]
+    #[inline(always)]
+    pub fn with_mut<R>(&self, f: impl FnOnce(*mut T) -> R) -> R {
+        #[cfg(feature = "checked")]
+        let _access = self.checker.write();
+
+        stress::yield_point();
+        f(self.data.get())
+    }
+
+    // Pointers carry no lifetime, so they are only checked when created.
+    #[track_caller]
+    #[inline(always)]
+    pub fn get(&self) -> ConstPtr<T> {
+        #[cfg(feature = "checked")]
+        drop(self.checker.read());
+
+        stress::yield_point();
+        ConstPtr(self.data.get())
+    }
+
+    #[track_caller]
+    #[inline(always)]
+    pub fn get_mut(&self) -> MutPtr<T> {
+        #[cfg(feature = "checked")]
+        drop(self.checker.write());
+
+        stress::yield_point();
+        MutPtr(self.data.get())
+    }
+
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+/// An immutable raw pointer into an [`UnsafeCell`], mirroring
+/// `loom::cell::ConstPtr`.
+#[derive(Debug)]
+pub struct ConstPtr<T>(*const T);
+
+impl<T> ConstPtr<T> {
+    /// # Safety
+    ///
+    /// Same as dereferencing a `*const T`.
+    #[inline(always)]
+    pub unsafe fn deref(&self) -> &T {
+        &*self.0
+    }
+
+    #[inline(always)]
+    pub fn with<R>(&self, f: impl FnOnce(*const T) -> R) -> R {
+        f(self.0)
+    }
+}
+
+/// A mutable raw pointer into an [`UnsafeCell`], mirroring
+/// `loom::cell::MutPtr`.
+#[derive(Debug)]
+pub struct MutPtr<T>(*mut T);
+
+impl<T> MutPtr<T> {
+    /// # Safety
+    ///
+    /// Same as dereferencing a `*mut T`.
+    #[allow(clippy::mut_from_ref)]
+    #[inline(always)]
+    pub unsafe fn deref(&self) -> &mut T {
+        &mut *self.0
+    }
+
+    #[inline(always)]
+    pub fn with<R>(&self, f: impl FnOnce(*mut T) -> R) -> R {
+        f(self.0)
+    }
+}
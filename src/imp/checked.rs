@@ -0,0 +1,100 @@
+//! Runtime detection of overlapping `UnsafeCell` accesses, enabled by the
+//! `checked` feature.
+
+use std::panic::Location;
+use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+
+/// Set in `state` while a mutable access is active.
+const WRITER: usize = 1 << (usize::BITS - 1);
+
+/// Tracks the accesses currently active on an `UnsafeCell`.
+#[derive(Debug, Default)]
+pub(crate) struct Checker {
+    /// Number of active immutable accesses, or `WRITER`.
+    state: AtomicUsize,
+
+    /// Call site of the latest access to begin. Only ever set from
+    /// `Location::caller`.
+    site: AtomicPtr<Location<'static>>,
+}
+
+/// Ends an access when dropped.
+pub(crate) struct Access<'a> {
+    checker: &'a Checker,
+    state: usize,
+}
+
+impl Checker {
+    /// Begin an immutable access, panicking if a mutable one is active.
+    #[track_caller]
+    pub(crate) fn read(&self) -> Access<'_> {
+        let caller = Location::caller();
+        let mut state = self.state.load(Ordering::Relaxed);
+
+        loop {
+            if state & WRITER != 0 {
+                self.race("immutable", caller);
+            }
+
+            match self.state.compare_exchange_weak(
+                state,
+                state + 1,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(actual) => state = actual,
+            }
+        }
+
+        self.begin(caller, 1)
+    }
+
+    /// Begin a mutable access, panicking if any other access is active.
+    #[track_caller]
+    pub(crate) fn write(&self) -> Access<'_> {
+        let caller = Location::caller();
+
+        if self
+            .state
+            .compare_exchange(0, WRITER, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            self.race("mutable", caller);
+        }
+
+        self.begin(caller, WRITER)
+    }
+
+    fn begin(&self, caller: &'static Location<'static>, state: usize) -> Access<'_> {
+        self.site
+            .store(caller as *const _ as *mut _, Ordering::Relaxed);
+
+        Access {
+            checker: self,
+            state,
+        }
+    }
+
+    #[cold]
+    #[track_caller]
+    fn race(&self, kind: &str, caller: &Location<'_>) -> ! {
+        // SAFETY: `site` is null or points to a `'static` location.
+        let other = unsafe { self.site.load(Ordering::Relaxed).as_ref() };
+
+        match other {
+            Some(other) => panic!(
+                "loomy: {kind} access to UnsafeCell at {caller} overlaps with an access at {other}"
+            ),
+            None => panic!(
+                "loomy: {kind} access to UnsafeCell at {caller} overlaps with another access"
+            ),
+        }
+    }
+}
+
+impl Drop for Access<'_> {
+    fn drop(&mut self) {
+        self.checker.state.fetch_sub(self.state, Ordering::Release);
+    }
+}
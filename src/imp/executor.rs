@@ -0,0 +1,168 @@
+//! A minimal executor running each task on its own thread.
+//!
+//! Task outputs and wakeups go through the backend's own primitives, so that
+//! under `loom` and `shuttle` every wake and poll is an interleaving point,
+//! while `std` runs the tasks on real threads. The remaining bookkeeping uses
+//! `std` primitives on purpose, as in loom's `thread::scope`, to keep the
+//! explored state space small.
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, PoisonError};
+use std::task::{Context, Poll};
+
+use super::future::{block_on, AtomicWaker};
+use super::sync::{Mutex, MutexGuard};
+use super::thread::{self, JoinHandle};
+
+/// Run futures concurrently, each on its own thread.
+///
+/// Each task is driven by [`block_on`] on a thread of its own, so the model
+/// explores how tasks interleave and wake each other. A [`Task`] is itself a
+/// future, so tasks may await one another. Spawned tasks are joined when the
+/// executor is dropped.
+///
+/// Under `loom`, every task counts towards `max_threads`.
+///
+/// ```rust
+/// use loomy::future::{block_on, Executor};
+///
+/// loomy::model(|| {
+///     let executor = Executor::new();
+///
+///     let task = executor.spawn(async { 1 });
+///
+///     assert_eq!(block_on(task), 1);
+/// });
+/// ```
+pub struct Executor {
+    threads: std::sync::Mutex<Vec<JoinHandle<()>>>,
+}
+
+/// A handle to a task spawned on an [`Executor`], resolving to its output.
+pub struct Task<T> {
+    shared: Arc<Shared<T>>,
+}
+
+struct Shared<T> {
+    output: Mutex<Option<T>>,
+    waker: AtomicWaker,
+}
+
+impl Executor {
+    /// Create a new executor with no tasks.
+    pub fn new() -> Executor {
+        Executor {
+            threads: std::sync::Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Spawn a task running `future`.
+    #[track_caller]
+    pub fn spawn<F>(&self, future: F) -> Task<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let shared = Arc::new(Shared {
+            output: Mutex::new(None),
+            waker: AtomicWaker::new(),
+        });
+
+        let task = Arc::clone(&shared);
+
+        let handle = thread::spawn(move || {
+            let output = block_on(future);
+
+            *lock(&task.output) = Some(output);
+            task.waker.wake();
+        });
+
+        self.threads().push(handle);
+
+        Task { shared }
+    }
+
+    /// Wait for every spawned task to finish.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any task panicked.
+    pub fn join(self) {
+        self.join_all();
+    }
+
+    fn join_all(&self) {
+        // Tasks may spawn further tasks while being joined.
+        loop {
+            let threads = std::mem::take(&mut *self.threads());
+
+            if threads.is_empty() {
+                break;
+            }
+
+            for handle in threads {
+                if let Err(payload) = handle.join() {
+                    std::panic::resume_unwind(payload);
+                }
+            }
+        }
+    }
+
+    fn threads(&self) -> std::sync::MutexGuard<'_, Vec<JoinHandle<()>>> {
+        self.threads.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Task<T> {
+    /// Check if the task has finished running.
+    pub fn is_finished(&self) -> bool {
+        lock(&self.shared.output).is_some()
+    }
+}
+
+impl<T> Future for Task<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        // Register before checking, so that a task finishing in between still
+        // wakes us.
+        self.shared.waker.register_by_ref(cx.waker());
+
+        match lock(&self.shared.output).take() {
+            Some(output) => Poll::Ready(output),
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl Default for Executor {
+    fn default() -> Executor {
+        Executor::new()
+    }
+}
+
+impl Drop for Executor {
+    fn drop(&mut self) {
+        if !thread::panicking() {
+            self.join_all();
+        }
+    }
+}
+
+impl fmt::Debug for Executor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Executor").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Debug for Task<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Task").finish_non_exhaustive()
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
@@ -0,0 +1,57 @@
+//! Future related synchronization primitives, mirroring `loom::future`.
+
+use std::future::Future;
+use std::pin::pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+pub use super::atomic_waker::AtomicWaker;
+pub use super::executor::{Executor, Task};
+
+/// Block the current thread, driving `f` to completion.
+///
+/// The thread is parked while `f` is pending, until its waker is woken.
+pub fn block_on<F>(f: F) -> F::Output
+where
+    F: Future,
+{
+    let mut f = pin!(f);
+
+    let signal = Arc::new(Signal {
+        thread: thread::current(),
+        notified: AtomicBool::new(false),
+    });
+
+    let waker = Waker::from(Arc::clone(&signal));
+    let mut cx = Context::from_waker(&waker);
+
+    loop {
+        if let Poll::Ready(value) = f.as_mut().poll(&mut cx) {
+            return value;
+        }
+
+        // Parking may wake spuriously, so wait for the waker itself.
+        while !signal.notified.swap(false, Ordering::Acquire) {
+            thread::park();
+        }
+    }
+}
+
+struct Signal {
+    thread: Thread,
+    notified: AtomicBool,
+}
+
+impl Wake for Signal {
+    fn wake(self: Arc<Signal>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Signal>) {
+        if !self.notified.swap(true, Ordering::Release) {
+            self.thread.unpark();
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! Statics which are reset between model executions.
+
+use std::fmt;
+use std::ops::Deref;
+use std::sync::LazyLock;
+
+/// A value for use in a `static`, initialized on first access.
+///
+/// Under `std` this is a plain `LazyLock` static. Under `loom` and `shuttle`,
+/// the value is instead initialized once per execution and dropped at its end,
+/// so global counters and registries built from loomy primitives can be
+/// modeled.
+///
+/// As the value is shared by every thread, `T` must be `Sync` under all
+/// backends.
+///
+/// ```rust
+/// use loomy::sync::atomic::{AtomicUsize, Ordering};
+///
+/// static NEXT_ID: loomy::Static<AtomicUsize> = loomy::Static::new(|| AtomicUsize::new(0));
+///
+/// loomy::model(|| {
+///     NEXT_ID.fetch_add(1, Ordering::Relaxed);
+/// });
+/// ```
+pub struct Static<T: Sync>(LazyLock<T>);
+
+impl<T: Sync> Static<T> {
+    /// Create a new static with the given initializing function.
+    pub const fn new(init: fn() -> T) -> Static<T> {
+        Static(LazyLock::new(init))
+    }
+}
+
+impl<T: Sync + 'static> Deref for Static<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Sync + 'static + fmt::Debug> fmt::Debug for Static<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Static").field(&**self).finish()
+    }
+}
+
+/// Declare lazily-initialized statics, mirroring the `lazy_static` crate.
+///
+/// Under `std` each static is a [`Static`]. Under `loom` and `shuttle`, this is
+/// their own `lazy_static!`, which resets the value between executions.
+///
+/// ```rust
+/// use std::collections::HashMap;
+///
+/// use loomy::sync::Mutex;
+///
+/// loomy::lazy_static! {
+///     static ref REGISTRY: Mutex<HashMap<u32, &'static str>> = Mutex::new(HashMap::new());
+/// }
+///
+/// loomy::model(|| {
+///     REGISTRY.lock().unwrap().insert(1, "one");
+/// });
+/// ```
+#[macro_export]
+macro_rules! lazy_static {
+    ($(#[$attr:meta])* $vis:vis static ref $name:ident : $t:ty = $init:expr; $($rest:tt)*) => {
+        $(#[$attr])*
+        $vis static $name: $crate::Static<$t> = $crate::Static::new(|| $init);
+
+        $crate::lazy_static!($($rest)*);
+    };
+    () => {};
+}
@@ -0,0 +1,215 @@
+//! Lock-order checking for the `std` backend's `Mutex` and `RwLock`, with the
+//! `checked` feature.
+//!
+//! Every blocking acquisition made while other locks are held records an edge
+//! from each held lock to the acquired one, in a graph shared by all threads.
+//! An acquisition which would close a cycle in that graph panics, reporting
+//! both orders, whether or not the threads involved actually deadlocked.
+//!
+//! Nodes are lock instances rather than classes, and are removed when the lock
+//! is dropped. Read and write acquisitions are treated alike, which is
+//! conservative for readers.
+
+use std::backtrace::Backtrace;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::panic::Location;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::PoisonError;
+
+type Site = &'static Location<'static>;
+
+/// The lock-order graph, keyed by lock id.
+static GRAPH: std::sync::Mutex<Option<HashMap<usize, HashMap<usize, Edge>>>> =
+    std::sync::Mutex::new(None);
+
+/// Hands out lock ids, starting from 1 as 0 marks an unassigned id.
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+
+thread_local! {
+    /// The locks held by this thread, in acquisition order.
+    static HELD: RefCell<Vec<(usize, Site)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The first acquisition of a lock while holding another.
+struct Edge {
+    /// Where the held lock was acquired.
+    held: Site,
+    /// Where the lock was acquired.
+    acquired: Site,
+    backtrace: Backtrace,
+}
+
+/// A lock's node in the graph, assigned on first use.
+pub(super) struct Id(AtomicUsize);
+
+/// A lock held by the current thread, released when dropped.
+pub(super) struct Held(usize);
+
+impl Id {
+    pub(super) const fn new() -> Id {
+        Id(AtomicUsize::new(0))
+    }
+
+    fn get(&self) -> usize {
+        let id = self.0.load(Ordering::Relaxed);
+
+        if id != 0 {
+            return id;
+        }
+
+        let new = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+
+        match self
+            .0
+            .compare_exchange(0, new, Ordering::Relaxed, Ordering::Relaxed)
+        {
+            Ok(_) => new,
+            Err(id) => id,
+        }
+    }
+
+    /// Record a blocking acquisition of this lock at `site`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock is already held by this thread, or if acquiring it
+    /// inverts an order recorded before.
+    pub(super) fn acquire(&self, site: Site) -> Held {
+        let id = self.get();
+        let held = HELD.with(|held| held.borrow().clone());
+
+        if let Some(&(_, first)) = held.iter().find(|&&(h, _)| h == id) {
+            panic!("loomy: lock acquired at {site} is already held by this thread, since {first}");
+        }
+
+        if !held.is_empty() {
+            let mut guard = graph();
+            let graph = guard.get_or_insert_with(HashMap::new);
+
+            for &(from, from_site) in &held {
+                if graph
+                    .get(&from)
+                    .is_some_and(|edges| edges.contains_key(&id))
+                {
+                    continue;
+                }
+
+                if let Some(path) = path(graph, id, from) {
+                    let report = report(graph, &path, from_site, site);
+                    drop(guard);
+                    panic!("{report}");
+                }
+
+                let edge = Edge {
+                    held: from_site,
+                    acquired: site,
+                    backtrace: Backtrace::force_capture(),
+                };
+
+                graph.entry(from).or_default().insert(id, edge);
+            }
+        }
+
+        self.held(id, site)
+    }
+
+    /// Record a non-blocking acquisition of this lock at `site`, which cannot
+    /// deadlock by itself.
+    pub(super) fn try_acquire(&self, site: Site) -> Held {
+        self.held(self.get(), site)
+    }
+
+    fn held(&self, id: usize, site: Site) -> Held {
+        HELD.with(|held| held.borrow_mut().push((id, site)));
+        Held(id)
+    }
+}
+
+impl Drop for Id {
+    fn drop(&mut self) {
+        let id = *self.0.get_mut();
+
+        if id == 0 {
+            return;
+        }
+
+        if let Some(graph) = graph().as_mut() {
+            graph.remove(&id);
+
+            for edges in graph.values_mut() {
+                edges.remove(&id);
+            }
+        }
+    }
+}
+
+impl Drop for Held {
+    fn drop(&mut self) {
+        HELD.with(|held| {
+            let mut held = held.borrow_mut();
+
+            if let Some(i) = held.iter().rposition(|&(id, _)| id == self.0) {
+                held.remove(i);
+            }
+        });
+    }
+}
+
+/// Find a path of edges from `from` to `to`.
+fn path(
+    graph: &HashMap<usize, HashMap<usize, Edge>>,
+    from: usize,
+    to: usize,
+) -> Option<Vec<usize>> {
+    let mut stack = vec![vec![from]];
+    let mut seen = HashSet::from([from]);
+
+    while let Some(path) = stack.pop() {
+        let last = *path.last().unwrap();
+
+        if last == to {
+            return Some(path);
+        }
+
+        for &next in graph.get(&last).into_iter().flat_map(HashMap::keys) {
+            if seen.insert(next) {
+                let mut path = path.clone();
+                path.push(next);
+                stack.push(path);
+            }
+        }
+    }
+
+    None
+}
+
+fn report(
+    graph: &HashMap<usize, HashMap<usize, Edge>>,
+    path: &[usize],
+    held: Site,
+    acquired: Site,
+) -> String {
+    let mut out = format!(
+        "loomy: lock order inversion, which may deadlock\n\n\
+         this thread acquires a lock at {acquired}, while holding the lock acquired at {held}:\n\
+         {}\n",
+        Backtrace::force_capture(),
+    );
+
+    for pair in path.windows(2) {
+        let edge = &graph[&pair[0]][&pair[1]];
+
+        out.push_str(&format!(
+            "\nbut a lock was previously acquired at {}, while holding the lock acquired at {}:\n\
+             {}\n",
+            edge.acquired, edge.held, edge.backtrace,
+        ));
+    }
+
+    out
+}
+
+fn graph() -> std::sync::MutexGuard<'static, Option<HashMap<usize, HashMap<usize, Edge>>>> {
+    GRAPH.lock().unwrap_or_else(PoisonError::into_inner)
+}
@@ -0,0 +1,375 @@
+//! The `std` backend's locks and `Barrier`.
+//!
+//! Blocking calls are recorded for the [watchdog](super::watchdog) and, with
+//! the `checked` feature, lock acquisitions are [order checked](super::lockdep).
+
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::panic::Location;
+use std::sync::{
+    BarrierWaitResult, LockResult, PoisonError, TryLockError, TryLockResult, WaitTimeoutResult,
+};
+use std::time::Duration;
+
+#[cfg(feature = "checked")]
+use super::lockdep::{Held, Id};
+use super::watchdog;
+#[cfg(not(feature = "checked"))]
+use unchecked::{Held, Id};
+
+/// Stand-ins for the lock-order bookkeeping, without the `checked` feature.
+#[cfg(not(feature = "checked"))]
+mod unchecked {
+    use std::panic::Location;
+
+    pub(super) struct Id;
+
+    pub(super) struct Held;
+
+    impl Id {
+        pub(super) const fn new() -> Id {
+            Id
+        }
+
+        #[inline(always)]
+        pub(super) fn acquire(&self, _: &'static Location<'static>) -> Held {
+            Held
+        }
+
+        #[inline(always)]
+        pub(super) fn try_acquire(&self, _: &'static Location<'static>) -> Held {
+            Held
+        }
+    }
+}
+
+/// Map the guard inside a `LockResult`.
+fn map_result<G, H>(result: LockResult<G>, f: impl FnOnce(G) -> H) -> LockResult<H> {
+    match result {
+        Ok(guard) => Ok(f(guard)),
+        Err(err) => Err(PoisonError::new(f(err.into_inner()))),
+    }
+}
+
+/// Map the guard inside a `TryLockResult`.
+fn map_try<G, H>(result: TryLockResult<G>, f: impl FnOnce(G) -> H) -> TryLockResult<H> {
+    match result {
+        Ok(guard) => Ok(f(guard)),
+        Err(TryLockError::Poisoned(err)) => Err(TryLockError::Poisoned(PoisonError::new(f(
+            err.into_inner()
+        )))),
+        Err(TryLockError::WouldBlock) => Err(TryLockError::WouldBlock),
+    }
+}
+
+/// `std::sync::Mutex`, recording blocked threads and, with the `checked`
+/// feature, checking the order in which locks are acquired.
+pub struct Mutex<T: ?Sized> {
+    id: Id,
+    inner: std::sync::Mutex<T>,
+}
+
+/// `std::sync::MutexGuard` for a loomy [`Mutex`].
+pub struct MutexGuard<'a, T: ?Sized + 'a> {
+    inner: std::sync::MutexGuard<'a, T>,
+    _held: Held,
+    id: &'a Id,
+}
+
+impl<T> Mutex<T> {
+    pub const fn new(value: T) -> Mutex<T> {
+        Mutex {
+            id: Id::new(),
+            inner: std::sync::Mutex::new(value),
+        }
+    }
+
+    pub fn into_inner(self) -> LockResult<T> {
+        self.inner.into_inner()
+    }
+}
+
+impl<T: ?Sized> Mutex<T> {
+    #[track_caller]
+    pub fn lock(&self) -> LockResult<MutexGuard<'_, T>> {
+        let site = Location::caller();
+        let _held = self.id.acquire(site);
+        let result = watchdog::blocking("Mutex::lock", site, || self.inner.lock());
+
+        map_result(result, |inner| MutexGuard {
+            inner,
+            _held,
+            id: &self.id,
+        })
+    }
+
+    #[track_caller]
+    pub fn try_lock(&self) -> TryLockResult<MutexGuard<'_, T>> {
+        map_try(self.inner.try_lock(), |inner| MutexGuard {
+            inner,
+            _held: self.id.try_acquire(Location::caller()),
+            id: &self.id,
+        })
+    }
+
+    pub fn is_poisoned(&self) -> bool {
+        self.inner.is_poisoned()
+    }
+
+    pub fn clear_poison(&self) {
+        self.inner.clear_poison()
+    }
+
+    pub fn get_mut(&mut self) -> LockResult<&mut T> {
+        self.inner.get_mut()
+    }
+}
+
+impl<'a, T: ?Sized> MutexGuard<'a, T> {
+    /// Stop holding the lock for lock-order checking, keeping what is needed
+    /// to reacquire it.
+    fn release(self) -> (std::sync::MutexGuard<'a, T>, &'a Id) {
+        (self.inner, self.id)
+    }
+}
+
+impl<T: Default> Default for Mutex<T> {
+    fn default() -> Mutex<T> {
+        Mutex::new(T::default())
+    }
+}
+
+impl<T> From<T> for Mutex<T> {
+    fn from(value: T) -> Mutex<T> {
+        Mutex::new(value)
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for Mutex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.inner, f)
+    }
+}
+
+impl<T: ?Sized> Deref for MutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: ?Sized> DerefMut for MutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for MutexGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.inner, f)
+    }
+}
+
+impl<T: ?Sized + fmt::Display> fmt::Display for MutexGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&*self.inner, f)
+    }
+}
+
+/// `std::sync::RwLock`, recording blocked threads and, with the `checked`
+/// feature, checking the order in which locks are acquired.
+pub struct RwLock<T: ?Sized> {
+    id: Id,
+    inner: std::sync::RwLock<T>,
+}
+
+/// `std::sync::RwLockReadGuard` for a loomy [`RwLock`].
+pub struct RwLockReadGuard<'a, T: ?Sized + 'a> {
+    inner: std::sync::RwLockReadGuard<'a, T>,
+    _held: Held,
+}
+
+/// `std::sync::RwLockWriteGuard` for a loomy [`RwLock`].
+pub struct RwLockWriteGuard<'a, T: ?Sized + 'a> {
+    inner: std::sync::RwLockWriteGuard<'a, T>,
+    _held: Held,
+}
+
+impl<T> RwLock<T> {
+    pub const fn new(value: T) -> RwLock<T> {
+        RwLock {
+            id: Id::new(),
+            inner: std::sync::RwLock::new(value),
+        }
+    }
+
+    pub fn into_inner(self) -> LockResult<T> {
+        self.inner.into_inner()
+    }
+}
+
+impl<T: ?Sized> RwLock<T> {
+    #[track_caller]
+    pub fn read(&self) -> LockResult<RwLockReadGuard<'_, T>> {
+        let site = Location::caller();
+        let _held = self.id.acquire(site);
+        let result = watchdog::blocking("RwLock::read", site, || self.inner.read());
+
+        map_result(result, |inner| RwLockReadGuard { inner, _held })
+    }
+
+    #[track_caller]
+    pub fn try_read(&self) -> TryLockResult<RwLockReadGuard<'_, T>> {
+        map_try(self.inner.try_read(), |inner| RwLockReadGuard {
+            inner,
+            _held: self.id.try_acquire(Location::caller()),
+        })
+    }
+
+    #[track_caller]
+    pub fn write(&self) -> LockResult<RwLockWriteGuard<'_, T>> {
+        let site = Location::caller();
+        let _held = self.id.acquire(site);
+        let result = watchdog::blocking("RwLock::write", site, || self.inner.write());
+
+        map_result(result, |inner| RwLockWriteGuard { inner, _held })
+    }
+
+    #[track_caller]
+    pub fn try_write(&self) -> TryLockResult<RwLockWriteGuard<'_, T>> {
+        map_try(self.inner.try_write(), |inner| RwLockWriteGuard {
+            inner,
+            _held: self.id.try_acquire(Location::caller()),
+        })
+    }
+
+    pub fn is_poisoned(&self) -> bool {
+        self.inner.is_poisoned()
+    }
+
+    pub fn clear_poison(&self) {
+        self.inner.clear_poison()
+    }
+
+    pub fn get_mut(&mut self) -> LockResult<&mut T> {
+        self.inner.get_mut()
+    }
+}
+
+impl<T: Default> Default for RwLock<T> {
+    fn default() -> RwLock<T> {
+        RwLock::new(T::default())
+    }
+}
+
+impl<T> From<T> for RwLock<T> {
+    fn from(value: T) -> RwLock<T> {
+        RwLock::new(value)
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for RwLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.inner, f)
+    }
+}
+
+impl<T: ?Sized> Deref for RwLockReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: ?Sized> Deref for RwLockWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: ?Sized> DerefMut for RwLockWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for RwLockReadGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.inner, f)
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for RwLockWriteGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.inner, f)
+    }
+}
+
+/// `std::sync::Condvar`, for use with a loomy [`Mutex`].
+///
+/// Waiting releases the mutex, which is then reacquired as if by `lock`.
+#[derive(Debug, Default)]
+pub struct Condvar(std::sync::Condvar);
+
+impl Condvar {
+    pub const fn new() -> Condvar {
+        Condvar(std::sync::Condvar::new())
+    }
+
+    #[track_caller]
+    pub fn wait<'a, T>(&self, guard: MutexGuard<'a, T>) -> LockResult<MutexGuard<'a, T>> {
+        let site = Location::caller();
+        let (inner, id) = guard.release();
+
+        let result = watchdog::blocking("Condvar::wait", site, || self.0.wait(inner));
+        let _held = id.acquire(site);
+
+        map_result(result, |inner| MutexGuard { inner, _held, id })
+    }
+
+    #[track_caller]
+    pub fn wait_timeout<'a, T>(
+        &self,
+        guard: MutexGuard<'a, T>,
+        dur: Duration,
+    ) -> LockResult<(MutexGuard<'a, T>, WaitTimeoutResult)> {
+        let site = Location::caller();
+        let (inner, id) = guard.release();
+
+        let result = watchdog::blocking("Condvar::wait_timeout", site, || {
+            self.0.wait_timeout(inner, dur)
+        });
+        let _held = id.acquire(site);
+
+        map_result(result, |(inner, timeout)| {
+            (MutexGuard { inner, _held, id }, timeout)
+        })
+    }
+
+    pub fn notify_one(&self) {
+        self.0.notify_one()
+    }
+
+    pub fn notify_all(&self) {
+        self.0.notify_all()
+    }
+}
+
+/// `std::sync::Barrier`, recording blocked threads.
+#[derive(Debug)]
+pub struct Barrier(std::sync::Barrier);
+
+impl Barrier {
+    pub fn new(n: usize) -> Barrier {
+        Barrier(std::sync::Barrier::new(n))
+    }
+
+    #[track_caller]
+    pub fn wait(&self) -> BarrierWaitResult {
+        watchdog::blocking("Barrier::wait", Location::caller(), || self.0.wait())
+    }
+}
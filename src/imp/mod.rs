@@ -0,0 +1,92 @@
+//! The `std` backend.
+//!
+//! Only items which also exist in the `loom` and `shuttle` backends are
+//! exported, so that code compiling against one backend compiles against all
+//! of them.
+
+use std::sync::atomic as raw_atomic;
+
+pub mod alloc;
+pub mod cell;
+pub mod future;
+
+pub mod hint {
+    pub use std::hint::{spin_loop, unreachable_unchecked};
+}
+
+pub mod sync {
+    pub use super::locks::{
+        Barrier, Condvar, Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard,
+    };
+    pub use std::sync::{
+        Arc, BarrierWaitResult, LazyLock, LockResult, Once, OnceLock, OnceState, TryLockResult,
+        WaitTimeoutResult, Weak,
+    };
+
+    pub mod atomic {
+        pub use crate::imp::atomic::*;
+    }
+
+    pub mod mpsc {
+        pub use std::sync::mpsc::{channel, Receiver, Sender};
+    }
+}
+
+pub mod thread;
+
+mod atomic;
+mod atomic_waker;
+#[cfg(feature = "checked")]
+mod checked;
+mod executor;
+mod lazy;
+#[cfg(feature = "checked")]
+mod lockdep;
+mod locks;
+pub(crate) mod stress;
+mod watchdog;
+
+pub use self::lazy::Static;
+pub use std::thread_local;
+
+use std::sync::Arc;
+
+use crate::model::{env_iterations, Builder};
+
+/// Run the model closure.
+///
+/// The closure is run once, or `LOOMY_ITERATIONS` times with randomized yields
+/// at every loomy atomic and `UnsafeCell` access.
+pub fn model<F>(f: F)
+where
+    F: Fn() + Sync + Send + 'static,
+{
+    check(&new_builder(), f)
+}
+
+pub(crate) fn new_builder() -> Builder {
+    Builder {
+        iterations: env_iterations().unwrap_or(1),
+        timeout: watchdog::env_timeout(),
+        ..Builder::base()
+    }
+}
+
+pub(crate) fn check<F>(builder: &Builder, f: F)
+where
+    F: Fn() + Sync + Send + 'static,
+{
+    let f = Arc::new(f);
+
+    stress::run(
+        builder.checked_iterations(),
+        builder.max_duration,
+        || match builder.timeout {
+            Some(timeout) => {
+                let f = Arc::clone(&f);
+                watchdog::watch(timeout, move || crate::tracked::execution(&*f));
+            }
+            None => crate::tracked::execution(&*f),
+        },
+    )
+}
@@ -0,0 +1,177 @@
+//! Randomized stress runs for the `std` backend.
+//!
+//! When the model closure is run more than once, every loomy atomic and
+//! `UnsafeCell` access may yield the current thread or spin for a short while,
+//! driven by a seeded PRNG. Failing iterations report their seed, which can be
+//! passed back through `LOOMY_SEED` (together with `LOOMY_ITERATIONS=1`) to
+//! replay the same injection pattern.
+//!
+//! Threads still run on the OS scheduler, so a seed narrows down a failure
+//! rather than reproducing it exactly.
+//!
+//! Each run keeps its own seed, which threads spawned through `loomy::thread`
+//! inherit, so stress tests running concurrently do not perturb one another.
+//! Threads spawned through `std::thread` are never perturbed.
+
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::{env, hint, thread};
+
+use crate::context;
+
+/// Number of stress runs in progress, outside of which accesses are never
+/// perturbed.
+static RUNS: AtomicUsize = AtomicUsize::new(0);
+
+/// An iteration of a stress run, shared by every thread taking part in it.
+#[derive(Clone)]
+struct Iteration {
+    seed: u64,
+
+    /// Hands out a distinct stream to every thread of the iteration.
+    streams: Arc<AtomicU64>,
+}
+
+/// A thread's PRNG stream within an iteration.
+pub(crate) struct Stream {
+    iteration: Iteration,
+    state: Cell<u64>,
+}
+
+fn env_seed() -> Option<u64> {
+    env::var("LOOMY_SEED")
+        .ok()
+        .map(|v| v.parse().expect("invalid value for `LOOMY_SEED`"))
+}
+
+/// Possibly yield or spin before an access to a loomy primitive.
+#[inline(always)]
+pub(crate) fn yield_point() {
+    if RUNS.load(Ordering::Relaxed) != 0 {
+        perturb();
+    }
+}
+
+#[cold]
+fn perturb() {
+    let Some(n) = context::with(|context| context.stress.as_ref().map(Stream::next)) else {
+        return;
+    };
+
+    match n % 8 {
+        0 | 1 => thread::yield_now(),
+        2 => {
+            for _ in 0..(n >> 8) % 64 {
+                hint::spin_loop();
+            }
+        }
+        _ => {}
+    }
+}
+
+impl Stream {
+    fn new(iteration: Iteration, stream: u64) -> Stream {
+        let state = splitmix(iteration.seed ^ splitmix(stream));
+
+        Stream {
+            iteration,
+            state: Cell::new(state),
+        }
+    }
+
+    /// Draw a stream for a thread spawned by the current one.
+    ///
+    /// Streams are drawn when spawning rather than on first access, so that
+    /// they follow the program order of each spawning thread.
+    pub(crate) fn spawn(&self) -> Stream {
+        let stream = self.iteration.streams.fetch_add(1, Ordering::Relaxed);
+
+        Stream::new(self.iteration.clone(), stream)
+    }
+
+    fn next(&self) -> u64 {
+        let mut state = self.state.get();
+
+        // xorshift64*
+        state ^= state >> 12;
+        state ^= state << 25;
+        state ^= state >> 27;
+        self.state.set(state);
+
+        state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+}
+
+fn splitmix(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    (z ^ (z >> 31)) | 1
+}
+
+/// Run `f` up to `iterations` times, stopping early once `max_duration` has
+/// elapsed.
+///
+/// Accesses are only perturbed when more than one iteration is requested, or
+/// when replaying a seed.
+pub(crate) fn run<F: Fn()>(iterations: usize, max_duration: Option<Duration>, f: F) {
+    let seed = env_seed();
+
+    if iterations == 1 && seed.is_none() {
+        f();
+        return;
+    }
+
+    struct Reset;
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            context::update(|context| context.stress = None);
+            RUNS.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    let start = Instant::now();
+    let base = seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0)
+    });
+
+    RUNS.fetch_add(1, Ordering::Relaxed);
+
+    // Stop perturbing even if an iteration fails.
+    let _reset = Reset;
+
+    for i in 0..iterations {
+        let seed = base.wrapping_add(i as u64);
+
+        let iteration = Iteration {
+            seed,
+            streams: Arc::new(AtomicU64::new(1)),
+        };
+
+        context::update(|context| context.stress = Some(Stream::new(iteration, 0)));
+
+        let result = panic::catch_unwind(AssertUnwindSafe(&f));
+
+        if let Err(payload) = result {
+            eprintln!(
+                "loomy: iteration {} of {} failed; reproduce with LOOMY_SEED={} LOOMY_ITERATIONS=1",
+                i + 1,
+                iterations,
+                seed,
+            );
+
+            panic::resume_unwind(payload);
+        }
+
+        if max_duration.is_some_and(|max| start.elapsed() >= max) {
+            break;
+        }
+    }
+}
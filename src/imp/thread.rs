@@ -0,0 +1,146 @@
+//! `std::thread`, carrying the model's state into spawned threads and
+//! recording them for the [watchdog](super::watchdog).
+
+use std::fmt;
+use std::io;
+use std::panic::Location;
+
+use super::watchdog;
+use crate::context::{self, Context};
+
+pub use std::thread::{
+    current, panicking, yield_now, AccessError, LocalKey, ScopedJoinHandle, Thread, ThreadId,
+};
+
+/// `std::thread::spawn`, running the thread as part of the current model.
+#[track_caller]
+pub fn spawn<F, T>(f: F) -> JoinHandle<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    Builder::new().spawn(f).expect("failed to spawn thread")
+}
+
+/// `std::thread::park`, recording the wait for the watchdog.
+#[track_caller]
+pub fn park() {
+    watchdog::blocking("thread::park", Location::caller(), std::thread::park)
+}
+
+/// `std::thread::scope`, running scoped threads as part of the current model.
+pub fn scope<'env, F, T>(f: F) -> T
+where
+    F: for<'scope> FnOnce(&'scope Scope<'scope, 'env>) -> T,
+{
+    std::thread::scope(|scope| f(Scope::new(scope)))
+}
+
+/// `std::thread::Builder`, running spawned threads as part of the current
+/// model.
+#[derive(Debug)]
+pub struct Builder(std::thread::Builder);
+
+/// `std::thread::JoinHandle`, recording joins for the watchdog.
+pub struct JoinHandle<T>(std::thread::JoinHandle<T>);
+
+/// `std::thread::Scope`, running scoped threads as part of the current model.
+#[repr(transparent)]
+pub struct Scope<'scope, 'env: 'scope>(std::thread::Scope<'scope, 'env>);
+
+impl Builder {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Builder {
+        Builder(std::thread::Builder::new())
+    }
+
+    pub fn name(self, name: String) -> Builder {
+        Builder(self.0.name(name))
+    }
+
+    pub fn stack_size(self, size: usize) -> Builder {
+        Builder(self.0.stack_size(size))
+    }
+
+    #[track_caller]
+    pub fn spawn<F, T>(self, f: F) -> io::Result<JoinHandle<T>>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let handle = self.0.spawn(inherit(Location::caller(), f))?;
+
+        Ok(JoinHandle(handle))
+    }
+}
+
+impl<T> JoinHandle<T> {
+    #[track_caller]
+    pub fn join(self) -> std::thread::Result<T> {
+        watchdog::blocking("JoinHandle::join", Location::caller(), || self.0.join())
+    }
+
+    pub fn thread(&self) -> &Thread {
+        self.0.thread()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.0.is_finished()
+    }
+}
+
+impl<T> fmt::Debug for JoinHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl<'scope, 'env> Scope<'scope, 'env> {
+    fn new<'a>(scope: &'a std::thread::Scope<'scope, 'env>) -> &'a Scope<'scope, 'env> {
+        // SAFETY: `Scope` is a transparent wrapper.
+        unsafe {
+            &*(scope as *const std::thread::Scope<'scope, 'env> as *const Scope<'scope, 'env>)
+        }
+    }
+
+    #[track_caller]
+    pub fn spawn<F, T>(&'scope self, f: F) -> ScopedJoinHandle<'scope, T>
+    where
+        F: FnOnce() -> T + Send + 'scope,
+        T: Send + 'scope,
+    {
+        self.0.spawn(inherit(Location::caller(), f))
+    }
+}
+
+impl fmt::Debug for Scope<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+/// Capture the context of the current thread for a thread it is spawning.
+pub(super) fn capture() -> Context {
+    context::with(|context| Context {
+        session: context.session,
+        execution: context.execution,
+        stress: context.stress.as_ref().map(|stress| stress.spawn()),
+        watchdog: context.watchdog,
+    })
+}
+
+/// Wrap the main function of a thread spawned at `site`, so that it runs as
+/// part of the spawning thread's model.
+fn inherit<F, T>(site: &'static Location<'static>, f: F) -> impl FnOnce() -> T
+where
+    F: FnOnce() -> T,
+{
+    let inherited = capture();
+
+    move || {
+        context::update(|context| *context = inherited);
+
+        let _spawned = watchdog::spawned(site);
+        f()
+    }
+}
@@ -0,0 +1,232 @@
+//! Hang detection for the `std` backend.
+//!
+//! With a [`timeout`](crate::model::Builder::timeout), every execution runs on
+//! a thread of its own, which the model's thread waits on. Meanwhile, the
+//! threads of the execution register themselves under its id, along with the
+//! loomy primitive each is blocked on, if any. Once the execution has run for
+//! longer than the timeout, the model's thread panics with the state of those
+//! threads, leaking them, as hung threads cannot be unwound.
+//!
+//! Outside of a watched execution, recording is a single relaxed load.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::panic::{self, AssertUnwindSafe, Location};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+use std::{env, thread};
+
+use crate::context;
+
+type Site = &'static Location<'static>;
+
+/// Number of watched executions running.
+static ACTIVE: AtomicUsize = AtomicUsize::new(0);
+
+/// The threads of every watched execution, by execution and thread key.
+static THREADS: Mutex<BTreeMap<(u64, u64), State>> = Mutex::new(BTreeMap::new());
+
+/// Hands out ids to watched executions and keys to threads alike.
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    /// This thread's key in `THREADS`, assigned on first use.
+    static KEY: u64 = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+}
+
+struct State {
+    thread: String,
+    role: Role,
+    blocked: Option<(&'static str, Site)>,
+}
+
+#[derive(Clone, Copy)]
+enum Role {
+    /// The thread running the model closure.
+    Model,
+    /// A thread spawned through `loomy::thread` at the given site.
+    Spawned(Site),
+}
+
+/// Timeout requested through `LOOMY_TIMEOUT`, in seconds, if any.
+pub(crate) fn env_timeout() -> Option<Duration> {
+    env::var("LOOMY_TIMEOUT").ok().map(|v| {
+        v.parse()
+            .map(Duration::from_secs_f64)
+            .expect("invalid value for `LOOMY_TIMEOUT`")
+    })
+}
+
+/// Run `f` as an execution on a thread of its own, panicking if it does not
+/// return within `timeout`.
+pub(super) fn watch(timeout: Duration, f: impl FnOnce() + Send + 'static) {
+    struct Active;
+
+    impl Drop for Active {
+        fn drop(&mut self) {
+            ACTIVE.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+
+    let mut inherited = super::thread::capture();
+    inherited.watchdog = Some(id);
+
+    // Named after the model's thread, which is usually named after the test.
+    let mut builder = thread::Builder::new();
+
+    if let Some(name) = thread::current().name() {
+        builder = builder.name(name.into());
+    }
+
+    let (sender, receiver) = mpsc::channel();
+
+    ACTIVE.fetch_add(1, Ordering::Relaxed);
+    let _active = Active;
+
+    builder
+        .spawn(move || {
+            context::update(|context| *context = inherited);
+
+            let _registered = register(Role::Model);
+            let _ = sender.send(panic::catch_unwind(AssertUnwindSafe(f)));
+        })
+        .expect("loomy: failed to spawn the execution thread");
+
+    match receiver.recv_timeout(timeout) {
+        Ok(Ok(())) => {}
+        Ok(Err(payload)) => panic::resume_unwind(payload),
+        Err(RecvTimeoutError::Timeout) => hung(id, timeout),
+        Err(RecvTimeoutError::Disconnected) => {
+            panic!("loomy: the execution thread exited without completing")
+        }
+    }
+}
+
+#[cold]
+fn hung(id: u64, timeout: Duration) -> ! {
+    let mut report = format!("loomy: execution still running after {timeout:?}\n\nthreads:\n");
+
+    let mut threads = lock(&THREADS);
+
+    // The hung threads are leaked, and so are not listed again.
+    let mut hung = threads.split_off(&(id, 0));
+    threads.append(&mut hung.split_off(&(id + 1, 0)));
+    drop(threads);
+
+    for state in hung.values() {
+        let _ = write!(report, "  {}", state.thread);
+
+        let _ = match state.role {
+            Role::Model => write!(report, " (running the model)"),
+            Role::Spawned(site) => write!(report, " (spawned at {site})"),
+        };
+
+        let _ = match state.blocked {
+            Some((what, site)) => writeln!(report, ": blocked on {what} at {site}"),
+            None => writeln!(report, ": running"),
+        };
+    }
+
+    report.push_str("\nonly threads spawned through `loomy::thread` are listed\n");
+
+    panic!("{report}");
+}
+
+/// Keeps the current thread listed until dropped.
+pub(super) struct Registered((u64, u64));
+
+impl Drop for Registered {
+    fn drop(&mut self) {
+        lock(&THREADS).remove(&self.0);
+    }
+}
+
+/// List the current thread, spawned at `site`, while its execution is watched.
+pub(super) fn spawned(site: Site) -> Option<Registered> {
+    if ACTIVE.load(Ordering::Relaxed) == 0 {
+        return None;
+    }
+
+    register(Role::Spawned(site))
+}
+
+fn register(role: Role) -> Option<Registered> {
+    let key = key()?;
+
+    let state = State {
+        thread: describe(),
+        role,
+        blocked: None,
+    };
+
+    lock(&THREADS).insert(key, state);
+
+    Some(Registered(key))
+}
+
+/// Run `f`, which blocks on `what` called at `site`, recording it while the
+/// current thread's execution is watched.
+#[inline(always)]
+pub(super) fn blocking<R>(what: &'static str, site: Site, f: impl FnOnce() -> R) -> R {
+    if ACTIVE.load(Ordering::Relaxed) == 0 {
+        return f();
+    }
+
+    let _blocked = Blocked::new(what, site);
+    f()
+}
+
+struct Blocked(Option<(u64, u64)>);
+
+impl Blocked {
+    #[cold]
+    fn new(what: &'static str, site: Site) -> Blocked {
+        let key = key();
+
+        if let Some(key) = key {
+            set_blocked(key, Some((what, site)));
+        }
+
+        Blocked(key)
+    }
+}
+
+impl Drop for Blocked {
+    fn drop(&mut self) {
+        if let Some(key) = self.0 {
+            set_blocked(key, None);
+        }
+    }
+}
+
+fn set_blocked(key: (u64, u64), blocked: Option<(&'static str, Site)>) {
+    if let Some(state) = lock(&THREADS).get_mut(&key) {
+        state.blocked = blocked;
+    }
+}
+
+/// The key of the current thread within its watched execution, if any.
+fn key() -> Option<(u64, u64)> {
+    let execution = context::with(|context| context.watchdog)?;
+
+    Some((execution, KEY.with(|key| *key)))
+}
+
+fn describe() -> String {
+    let thread = thread::current();
+
+    match thread.name() {
+        Some(name) => format!("thread '{name}'"),
+        None => format!("thread {:?}", thread.id()),
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // Every update is a whole insert, removal or assignment, so a panic cannot
+    // leave the state torn.
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
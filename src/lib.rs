@@ -2,7 +2,7 @@
 //!
 //! Import common types and modules like `UnsafeCell`, `thread`, `Arc`,
 //! `AtomicI32`, etc from this crate, and run loom tests from the command line
-//! with `cargo test --features loomy/enable`.
+//! with `RUSTFLAGS="--cfg loom" cargo test`.
 //!
 //! ## Example
 //!
@@ -10,13 +10,18 @@
 //!
 //! ```sh
 //! $ cargo test
-//! $ cargo test --features loomy/enable
+//! $ RUSTFLAGS="--cfg loom" cargo test
 //! ```
 //!
-//! When `loomy/enable` is set, then the code will be tested as a loomy model,
+//! When `--cfg loom` is set, then the code will be tested as a loomy model,
 //! otherwise all types default to their `std` equivalents, and the code will be
 //! tested as normal.
 //!
+//! `--cfg loom` is the same switch used by crates such as `tokio` and `bytes`,
+//! so a single `RUSTFLAGS` flips the whole dependency graph. The `loomy/enable`
+//! feature is still accepted, but fails to compile without `--cfg loom`, as
+//! `cfg(loom)`-gated dependencies would silently keep using `std`.
+//!
 //! ```rust
 //! // Note the use of `loomy` instead of `std` or `loom`.
 //! use loomy::{
@@ -89,55 +94,342 @@
 //! # test_simple();
 //! ```
 //!
+//! ## Stress testing with `std`
+//!
+//! By default, `loomy::model` runs the closure once when using `std`. Setting
+//! `LOOMY_ITERATIONS` runs it that many times instead, randomly yielding or
+//! spinning at every loomy atomic and `UnsafeCell` access to shake out more
+//! interleavings:
+//!
+//! ```sh
+//! $ LOOMY_ITERATIONS=10000 cargo test
+//! ```
+//!
+//! The seed of a failing iteration is printed to stderr, and can be replayed
+//! with `LOOMY_SEED=<seed> LOOMY_ITERATIONS=1`. Every run keeps its own seed,
+//! so this holds with tests running in parallel, as long as the model spawns
+//! its threads through `loomy::thread`.
+//!
+//! A hung test would otherwise only time out in CI. Setting `LOOMY_TIMEOUT`
+//! (or [`timeout`](model::Builder::timeout)) fails any execution running
+//! for longer than that many seconds, listing every thread it spawned through
+//! `loomy::thread` and the loomy primitive each is blocked on. Only the hung
+//! test fails, while its threads are leaked:
+//!
+//! ```sh
+//! $ LOOMY_TIMEOUT=60 cargo test
+//! ```
+//!
+//! Enabling the `checked` feature additionally tracks the accesses active on
+//! every `UnsafeCell`, panicking with both call sites when a mutable access
+//! overlaps with any other, much like `loom` does:
+//!
+//! ```sh
+//! $ LOOMY_ITERATIONS=10000 cargo test --features loomy/checked
+//! ```
+//!
+//! Pointers returned by `UnsafeCell::get` and `get_mut` are only checked when
+//! created.
+//!
+//! The `checked` feature also accounts for every allocation made through
+//! `loomy::alloc`, failing the execution on leaks, double deallocations and
+//! deallocations with a mismatched `Layout`. Under `std`, `sync::Mutex` and
+//! `sync::RwLock` additionally record the order in which locks are acquired,
+//! panicking with both acquisition backtraces as soon as two locks are taken
+//! in opposite orders, even if that run did not deadlock. None of these checks
+//! has any effect under `loom`, which performs its own.
+//!
+//! ## Shuttle
+//!
+//! Enabling the `shuttle` feature runs the same code under
+//! [shuttle](https://docs.rs/shuttle)'s randomized scheduler instead, which
+//! scales to larger tests than loom's exhaustive exploration:
+//!
+//! ```sh
+//! $ cargo test --features loomy/shuttle
+//! ```
+//!
+//! `loomy::model` then runs the closure `LOOMY_ITERATIONS` times (1000 by
+//! default) with `shuttle::check_random`. Set
+//! [`pct_depth`](model::Builder::pct_depth) to use `shuttle`'s PCT scheduler.
+//! Note that `shuttle` does not detect data races on `UnsafeCell`, though the
+//! `checked` feature described above catches accesses overlapping in time.
+//!
+//! ## Configuring the model
+//!
+//! [`model::Builder`] mirrors `loom::model::Builder`, and is forwarded to loom
+//! when `--cfg loom` is set. With `std` and `shuttle`, the closure is run
+//! [`iterations`](model::Builder::iterations) times instead.
+//!
+//! ```rust
+//! use loomy::{thread, sync::Arc, sync::atomic::{AtomicUsize, Ordering}};
+//!
+//! let mut builder = loomy::model::Builder::new();
+//! builder.preemption_bound = Some(3);
+//! builder.iterations = 100;
+//!
+//! builder.check(|| {
+//!     let n = Arc::new(AtomicUsize::new(0));
+//!     let n2 = Arc::clone(&n);
+//!
+//!     let t = thread::spawn(move || n2.fetch_add(1, Ordering::Relaxed));
+//!     n.fetch_add(1, Ordering::Relaxed);
+//!     t.join().unwrap();
+//!
+//!     assert_eq!(n.load(Ordering::Relaxed), 2);
+//! });
+//! ```
+//!
+//! ## Portability
+//!
+//! Every backend exports the same, explicitly curated set of items: only what
+//! exists in `std`, `loom` and `shuttle` alike, with matching signatures. Code
+//! which compiles against one backend therefore compiles against the others,
+//! rather than breaking only once CI flips the backend. `tests/parity.rs`
+//! checks each exported path and signature under every backend.
+//!
+//! Where a backend lacks an item, loomy fills the gap: `loom` has no
+//! `thread::scope`, `sync::Once`, `sync::Weak` or a working `sync::Barrier`,
+//! so loomy provides them on top of loom's own primitives. Its `sync::Arc` is
+//! replaced too, keeping both reference counts in loom atomics so that races
+//! between `upgrade` and `drop` are explored. Likewise, `sync::OnceLock`
+//! and `sync::LazyLock` are built on the backend's `Once` under both `loom`
+//! and `shuttle`.
+//!
+//! Items specific to a backend, such as `loom::sync::Notify` or
+//! `std::cell::RefCell`, should be imported from that backend directly.
+//!
 //! ## A note on `UnsafeCell`
 //!
-//! `UnsafeCell` in `loom` has a closure-based API. When using `std` types,
-//! `UnsafeCell` is wrapped in order to provide the same API.
+//! `UnsafeCell` in `loom` has a closure-based API (`with`/`with_mut`) as well
+//! as a guard-based API (`get`/`get_mut`) returning `ConstPtr`/`MutPtr`. When
+//! using `std` types, `UnsafeCell` is wrapped in order to provide the same API.
+//!
+//! ```rust
+//! use loomy::cell::UnsafeCell;
+//!
+//! loomy::model(|| {
+//!     let cell = UnsafeCell::new(1);
+//!
+//!     let ptr = cell.get_mut();
+//!     unsafe { *ptr.deref() += 1 };
+//!     drop(ptr);
+//!
+//!     assert_eq!(cell.get().with(|n| unsafe { *n }), 2);
+//! });
+//! ```
 
-#[cfg(feature = "enable")]
-mod imp {
-    pub use loom::*;
-}
+// The crate example shows a `#[test]` function, which is invoked manually.
+#![allow(clippy::test_attr_in_doctest)]
 
-#[cfg(not(feature = "enable"))]
-mod imp {
-    pub use std::{alloc, hint, sync, thread};
+#[cfg(all(feature = "enable", not(loom)))]
+compile_error!(
+    "the `loomy/enable` feature requires `--cfg loom`, \
+     e.g. `RUSTFLAGS=\"--cfg loom\" cargo test`"
+);
 
-    pub mod cell {
-        pub use std::cell::*;
+#[cfg(all(feature = "shuttle", loom))]
+compile_error!("the `shuttle` feature cannot be combined with `--cfg loom`");
 
-        #[derive(Debug, Default)]
-        pub struct UnsafeCell<T>(std::cell::UnsafeCell<T>);
+#[cfg(loom)]
+#[path = "loom/mod.rs"]
+mod imp;
 
-        impl<T> From<T> for UnsafeCell<T> {
-            #[inline(always)]
-            fn from(t: T) -> Self {
-                Self(std::cell::UnsafeCell::new(t))
-            }
-        }
+#[cfg(all(feature = "shuttle", not(loom)))]
+#[path = "shuttle.rs"]
+mod imp;
+
+#[cfg(not(any(loom, feature = "shuttle")))]
+mod imp;
+
+pub use self::imp::*;
+pub use self::observe::{observe, outcomes, Outcomes};
+pub use self::tracked::Tracked;
 
-        impl<T> UnsafeCell<T> {
-            #[inline(always)]
-            pub fn new(data: T) -> UnsafeCell<T> {
-                UnsafeCell(std::cell::UnsafeCell::new(data))
-            }
-
-            #[inline(always)]
-            pub fn with<R>(&self, f: impl FnOnce(*const T) -> R) -> R {
-                f(self.0.get())
-            }
-
-            #[inline(always)]
-            pub fn with_mut<R>(&self, f: impl FnOnce(*mut T) -> R) -> R {
-                f(self.0.get())
-            }
+pub mod lincheck;
+pub mod litmus;
+pub mod model;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+
+mod context;
+mod observe;
+mod tracked;
+
+/// Run `a` and `b` concurrently, returning both results.
+///
+/// `b` is run on a scoped thread while `a` runs on the current one. A panic in
+/// either closure is propagated.
+///
+/// ```rust
+/// loomy::model(|| {
+///     let mut v = vec![1, 2, 3, 4];
+///     let (left, right) = v.split_at_mut(2);
+///
+///     let (a, b) = loomy::join(|| left.iter().sum::<i32>(), || right.iter().sum::<i32>());
+///
+///     assert_eq!((a, b), (3, 7));
+/// });
+/// ```
+pub fn join<A, B, RA, RB>(a: A, b: B) -> (RA, RB)
+where
+    A: FnOnce() -> RA + Send,
+    B: FnOnce() -> RB + Send,
+    RA: Send,
+    RB: Send,
+{
+    thread::scope(|s| {
+        let b = s.spawn(b);
+        let a = a();
+
+        match b.join() {
+            Ok(b) => (a, b),
+            Err(payload) => std::panic::resume_unwind(payload),
         }
-    }
+    })
+}
+
+/// Run `f` for each of `threads` threads, given its index, returning every
+/// thread's result in order.
+///
+/// Each thread but the last runs on a scoped thread, while the last runs on the
+/// current one, sparing `loom` a thread. A panic in any thread is propagated.
+#[doc(hidden)]
+pub fn __run_threads<F, R>(threads: usize, f: F) -> Vec<R>
+where
+    F: Fn(usize) -> R + Sync,
+    R: Send,
+{
+    let Some(last) = threads.checked_sub(1) else {
+        return Vec::new();
+    };
+
+    let f = &f;
+
+    thread::scope(|s| {
+        let handles = (0..last).map(|i| s.spawn(move || f(i))).collect::<Vec<_>>();
+
+        let last = f(last);
 
-    #[inline(always)]
-    pub fn model<F: FnOnce()>(f: F) {
-        f()
-    }
+        let mut results = handles
+            .into_iter()
+            .map(|handle| match handle.join() {
+                Ok(result) => result,
+                Err(payload) => std::panic::resume_unwind(payload),
+            })
+            .collect::<Vec<_>>();
+
+        results.push(last);
+        results
+    })
 }
 
-pub use self::imp::*;
+/// Define a test whose body is run with `loomy::model`.
+///
+/// The body is passed to [`model::Builder::check`], configured with the
+/// attribute arguments:
+///
+/// - `preemption_bound = N`, `max_branches = N`, `max_threads = N`,
+///   `max_permutations = N`: forwarded to `loom`.
+/// - `max_duration = SECS`: forwarded to `loom`, and stops starting new
+///   iterations with `std` and `shuttle`.
+/// - `checkpoint_file = "path"`: forwarded to `loom`.
+/// - `std_iterations = N`: the number of iterations to run with `std` or
+///   `shuttle`, overriding `LOOMY_ITERATIONS`.
+/// - `std_timeout = SECS`: fail an execution running for longer with `std`,
+///   listing what each thread is blocked on, overriding `LOOMY_TIMEOUT`.
+/// - `pct_depth = N`: use shuttle's PCT scheduler with this depth.
+/// - `ignore_in_std`: marks the test `#[ignore]` unless `--cfg loom` is set or
+///   the `shuttle` feature is enabled.
+///
+/// ```rust
+/// #[loomy::test(preemption_bound = 3, std_iterations = 100)]
+/// fn test_counter() {
+///     use loomy::{thread, sync::Arc, sync::atomic::{AtomicUsize, Ordering}};
+///
+///     let n = Arc::new(AtomicUsize::new(0));
+///     let n2 = Arc::clone(&n);
+///
+///     let t = thread::spawn(move || n2.fetch_add(1, Ordering::Relaxed));
+///     n.fetch_add(1, Ordering::Relaxed);
+///     t.join().unwrap();
+///
+///     assert_eq!(n.load(Ordering::Relaxed), 2);
+/// }
+/// ```
+pub use loomy_macros::test;
+
+/// Run a memory-model litmus test with [`outcomes`], returning a
+/// [`litmus::Report`] of every outcome observed.
+///
+/// A litmus test is made up of:
+///
+/// - `shared x = 0, y = 0;`: shared locations, each an `AtomicUsize` with the
+///   given initial value, or `0` if omitted.
+/// - `thread { ... }`: the instructions run by a thread. Every top-level `let`
+///   declares a register, which must be a `usize`. `Ordering`'s variants and
+///   `fence` are in scope.
+/// - `forbidden <condition>;`: an outcome which must never be observed. The
+///   model fails as soon as an execution satisfies the condition.
+/// - `allowed <condition>;`: an outcome which may be observed. The report
+///   records whether any execution satisfied it.
+///
+/// Conditions may refer to every register, as well as to the final value of
+/// every shared location.
+///
+/// ```rust
+/// // Message passing: `y`'s release/acquire pair makes the write to `x`
+/// // visible.
+/// let report = loomy::litmus! {
+///     shared x, y;
+///
+///     thread {
+///         x.store(1, Relaxed);
+///         y.store(1, Release);
+///     }
+///
+///     thread {
+///         let r1 = y.load(Acquire);
+///         let r2 = x.load(Relaxed);
+///     }
+///
+///     forbidden r1 == 1 && r2 == 0;
+///     allowed r1 == 0 && r2 == 0;
+///     allowed r1 == 1 && r2 == 1;
+/// };
+///
+/// println!("{report}");
+///
+/// // `loom` explores every interleaving, so every allowed outcome is reached.
+/// if cfg!(loom) {
+///     assert!(report.allowed().all(|(_, observed)| observed));
+/// }
+/// ```
+///
+/// As with [`outcomes`], the threads run `LOOMY_ITERATIONS` times under `std`,
+/// so which allowed outcomes are reached is down to the OS scheduler. Note that
+/// `loom` does not model load buffering, so such outcomes are never observed.
+/// It also treats `SeqCst` accesses as `AcqRel`: store buffering with `SeqCst`
+/// stores and loads fails under `loom` with ``forbidden outcome `r1 == 0 && r2
+/// == 0` observed``, even though the language forbids that outcome. Use
+/// `SeqCst` fences between the accesses to rule it out under `loom`.
+pub use loomy_macros::litmus;
+
+#[doc(hidden)]
+#[cfg(any(loom, feature = "shuttle"))]
+#[macro_export]
+macro_rules! __ignore_in_std {
+    ($($item:tt)*) => {
+        $($item)*
+    };
+}
+
+#[doc(hidden)]
+#[cfg(not(any(loom, feature = "shuttle")))]
+#[macro_export]
+macro_rules! __ignore_in_std {
+    ($($item:tt)*) => {
+        #[ignore = "only run with `--cfg loom` or the `shuttle` feature"]
+        $($item)*
+    };
+}
@@ -0,0 +1,229 @@
+//! Linearizability checking for concurrent data structures.
+//!
+//! Each thread runs its own list of operations against a shared object, while
+//! the invocation and response of every operation are recorded in a history.
+//! A history is linearizable if some sequential order of its operations,
+//! consistent with the order in which they ran in real time, produces the same
+//! results on the sequential [`Spec`].
+//!
+//! ```rust
+//! use loomy::lincheck::{self, Spec};
+//! use loomy::sync::Mutex;
+//!
+//! #[derive(Debug)]
+//! enum Op {
+//!     Push(u8),
+//!     Pop,
+//! }
+//!
+//! #[derive(Clone, Default)]
+//! struct Stack(Vec<u8>);
+//!
+//! impl Spec for Stack {
+//!     type Op = Op;
+//!     type Ret = Option<u8>;
+//!
+//!     fn apply(&mut self, op: &Op) -> Option<u8> {
+//!         match *op {
+//!             Op::Push(n) => {
+//!                 self.0.push(n);
+//!                 None
+//!             }
+//!             Op::Pop => self.0.pop(),
+//!         }
+//!     }
+//! }
+//!
+//! lincheck::check(
+//!     Stack::default(),
+//!     vec![vec![Op::Push(1), Op::Pop], vec![Op::Push(2)]],
+//!     || Mutex::new(Vec::new()),
+//!     |stack, op| {
+//!         let mut stack = stack.lock().unwrap();
+//!
+//!         match *op {
+//!             Op::Push(n) => {
+//!                 stack.push(n);
+//!                 None
+//!             }
+//!             Op::Pop => stack.pop(),
+//!         }
+//!     },
+//! );
+//! ```
+//!
+//! Timestamps are taken outside of the model, so recording the history is never
+//! an interleaving point.
+
+use std::fmt::{self, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::model::Builder;
+
+/// A sequential specification of a concurrent object.
+pub trait Spec: Clone {
+    /// An operation on the object.
+    type Op;
+
+    /// The result of an operation.
+    type Ret: PartialEq;
+
+    /// Apply `op` to the object, returning its result.
+    fn apply(&mut self, op: &Self::Op) -> Self::Ret;
+}
+
+/// Run `threads` against the object created by `new` with
+/// [`model`](crate::model), panicking with the offending history unless every
+/// execution is linearizable with respect to `spec`.
+///
+/// Each inner list of operations is run in order on its own thread, calling
+/// `apply` for each operation. `spec` is the initial state of the sequential
+/// object, and must match the state of the object returned by `new`.
+pub fn check<S, T, N, F>(spec: S, threads: Vec<Vec<S::Op>>, new: N, apply: F)
+where
+    S: Spec + Send + Sync + 'static,
+    S::Op: fmt::Debug + Send + Sync + 'static,
+    S::Ret: fmt::Debug + Send + 'static,
+    T: Sync,
+    N: Fn() -> T + Sync + Send + 'static,
+    F: Fn(&T, &S::Op) -> S::Ret + Sync + Send + 'static,
+{
+    check_with(&Builder::new(), spec, threads, new, apply);
+}
+
+/// Like [`check`], configuring the model with `builder`.
+pub fn check_with<S, T, N, F>(
+    builder: &Builder,
+    spec: S,
+    threads: Vec<Vec<S::Op>>,
+    new: N,
+    apply: F,
+) where
+    S: Spec + Send + Sync + 'static,
+    S::Op: fmt::Debug + Send + Sync + 'static,
+    S::Ret: fmt::Debug + Send + 'static,
+    T: Sync,
+    N: Fn() -> T + Sync + Send + 'static,
+    F: Fn(&T, &S::Op) -> S::Ret + Sync + Send + 'static,
+{
+    assert!(
+        !threads.is_empty(),
+        "loomy: lincheck needs at least one thread"
+    );
+
+    builder.check(move || {
+        let object = new();
+        let clock = AtomicUsize::new(0);
+
+        let run = |thread: usize| {
+            threads[thread]
+                .iter()
+                .map(|op| {
+                    let invoked = clock.fetch_add(1, Ordering::SeqCst);
+                    let ret = apply(&object, op);
+                    let returned = clock.fetch_add(1, Ordering::SeqCst);
+
+                    Call {
+                        thread,
+                        op,
+                        ret,
+                        invoked,
+                        returned,
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let history = crate::__run_threads(threads.len(), run)
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+
+        let mut done = vec![false; history.len()];
+
+        if !linearize(&history, &mut done, spec.clone()) {
+            panic!("loomy: history is not linearizable:\n{}", format(&history));
+        }
+    });
+}
+
+/// A completed operation in a history.
+struct Call<'a, O, R> {
+    thread: usize,
+    op: &'a O,
+    ret: R,
+    invoked: usize,
+    returned: usize,
+}
+
+/// Search for a linearization of the calls not yet `done`, starting from
+/// `spec`.
+fn linearize<S: Spec>(history: &[Call<'_, S::Op, S::Ret>], done: &mut [bool], spec: S) -> bool {
+    // A call may be linearized next only if it was invoked before every other
+    // pending call returned.
+    let Some(deadline) = pending(history, done).map(|c| c.returned).min() else {
+        return true;
+    };
+
+    for i in 0..history.len() {
+        let call = &history[i];
+
+        if done[i] || call.invoked > deadline {
+            continue;
+        }
+
+        let mut next = spec.clone();
+
+        if next.apply(call.op) != call.ret {
+            continue;
+        }
+
+        done[i] = true;
+
+        if linearize(history, done, next) {
+            return true;
+        }
+
+        done[i] = false;
+    }
+
+    false
+}
+
+fn pending<'a, 'b, O, R>(
+    history: &'a [Call<'b, O, R>],
+    done: &'a [bool],
+) -> impl Iterator<Item = &'a Call<'b, O, R>> {
+    history
+        .iter()
+        .zip(done)
+        .filter(|(_, &done)| !done)
+        .map(|(call, _)| call)
+}
+
+/// Format `history` as its invocations and responses, in real-time order.
+fn format<O: fmt::Debug, R: fmt::Debug>(history: &[Call<'_, O, R>]) -> String {
+    let mut events = Vec::new();
+
+    for call in history {
+        events.push((call.invoked, call, false));
+        events.push((call.returned, call, true));
+    }
+
+    events.sort_by_key(|&(time, ..)| time);
+
+    let mut out = String::new();
+
+    for (_, call, returned) in events {
+        let thread = call.thread;
+        let op = call.op;
+
+        let _ = if returned {
+            writeln!(out, "  thread {thread}: {op:?} returned {:?}", call.ret)
+        } else {
+            writeln!(out, "  thread {thread}: {op:?} invoked")
+        };
+    }
+
+    out
+}
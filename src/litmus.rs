@@ -0,0 +1,136 @@
+//! Reports produced by the [`litmus!`](crate::litmus!) macro.
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::Outcomes;
+
+#[doc(hidden)]
+pub const __OUTCOMES: &str = "loomy::litmus::outcomes";
+
+#[doc(hidden)]
+pub const __ALLOWED: &str = "loomy::litmus::allowed";
+
+/// The final register values of a single execution of a litmus test.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Outcome {
+    registers: Vec<(&'static str, usize)>,
+}
+
+/// The outcomes observed across every execution of a litmus test.
+///
+/// The `Display` implementation lists each outcome, followed by every `allowed`
+/// condition and whether it was observed.
+pub struct Report {
+    outcomes: BTreeSet<Outcome>,
+    allowed: Vec<(&'static str, bool)>,
+}
+
+impl Outcome {
+    #[doc(hidden)]
+    pub fn __new(registers: Vec<(&'static str, usize)>) -> Outcome {
+        Outcome { registers }
+    }
+
+    /// Get the final value of `register`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the test has no register named `register`.
+    pub fn get(&self, register: &str) -> usize {
+        match self.registers.iter().find(|(name, _)| *name == register) {
+            Some(&(_, value)) => value,
+            None => panic!("loomy: litmus test has no register `{register}`"),
+        }
+    }
+
+    /// Iterate over every register and its final value, in declaration order.
+    pub fn registers(&self) -> impl Iterator<Item = (&'static str, usize)> + '_ {
+        self.registers.iter().copied()
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (name, value)) in self.registers.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+
+            write!(f, "{name} = {value}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.registers.iter().copied())
+            .finish()
+    }
+}
+
+impl Report {
+    #[doc(hidden)]
+    pub fn __new(outcomes: &Outcomes, allowed: &[&'static str]) -> Report {
+        let reached = outcomes.get::<Vec<bool>>(__ALLOWED);
+
+        Report {
+            outcomes: outcomes.get(__OUTCOMES),
+            allowed: allowed
+                .iter()
+                .enumerate()
+                .map(|(i, &condition)| (condition, reached.iter().any(|r| r[i])))
+                .collect(),
+        }
+    }
+
+    /// Iterate over every distinct outcome observed.
+    pub fn outcomes(&self) -> impl Iterator<Item = &Outcome> + '_ {
+        self.outcomes.iter()
+    }
+
+    /// Iterate over every `allowed` condition, in declaration order, along with
+    /// whether any execution satisfied it.
+    pub fn allowed(&self) -> impl Iterator<Item = (&'static str, bool)> + '_ {
+        self.allowed.iter().copied()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} outcome(s) observed:", self.outcomes.len())?;
+
+        for outcome in &self.outcomes {
+            writeln!(f, "  {outcome}")?;
+        }
+
+        for (condition, observed) in &self.allowed {
+            let observed = if *observed {
+                "observed"
+            } else {
+                "not observed"
+            };
+            writeln!(f, "allowed `{condition}`: {observed}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Report")
+            .field("outcomes", &self.outcomes)
+            .field("allowed", &self.allowed)
+            .finish()
+    }
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn __forbidden(condition: &str, outcome: &Outcome) -> ! {
+    panic!("loomy: forbidden outcome `{condition}` observed with {outcome}");
+}
@@ -0,0 +1,26 @@
+//! `loom::alloc`, with `realloc` built on its leak-tracked allocations.
+
+use std::ptr;
+
+pub use loom::alloc::{alloc, alloc_zeroed, dealloc, Layout, Track};
+
+/// Reallocate memory, mirroring `std::alloc::realloc`.
+///
+/// Loom has no `realloc` of its own, so the memory is moved to a new
+/// allocation, which loom then tracks for leaks in place of the old one.
+///
+/// # Safety
+///
+/// See `std::alloc::realloc`.
+#[track_caller]
+pub unsafe fn realloc(ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+    let new = alloc(Layout::from_size_align_unchecked(new_size, layout.align()));
+
+    // On failure, the original allocation is left untouched.
+    if !new.is_null() {
+        ptr::copy_nonoverlapping(ptr, new, layout.size().min(new_size));
+        dealloc(ptr, layout);
+    }
+
+    new
+}
@@ -0,0 +1,686 @@
+//! `Arc` and `Weak` with reference counts kept in loom atomics.
+//!
+//! `loom::sync::Arc` models its reference count internally but has no `Weak`,
+//! so this follows `std`'s algorithm instead, letting loom explore the races
+//! between `upgrade`, `downgrade`, `get_mut` and `drop`.
+//!
+//! Unsized values are supported, but as `CoerceUnsized` is unstable, they are
+//! moved in from a `Box` or a unique `std::sync::Arc`, like with loom's own.
+
+use std::alloc::{self, Layout};
+use std::borrow::Borrow;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::mem::{self, ManuallyDrop};
+use std::panic::{RefUnwindSafe, UnwindSafe};
+use std::pin::Pin;
+use std::ptr::{self, NonNull};
+use std::{fmt, ops};
+
+use loom::alloc::Track;
+use loom::hint;
+use loom::sync::atomic::{fence, AtomicUsize, Ordering};
+
+/// A thread-safe reference-counting pointer, mirroring `std::sync::Arc`.
+pub struct Arc<T: ?Sized> {
+    ptr: NonNull<ArcInner<T>>,
+    phantom: PhantomData<ArcInner<T>>,
+}
+
+/// A non-owning reference to an [`Arc`], mirroring `std::sync::Weak`.
+pub struct Weak<T: ?Sized> {
+    // `None` for weak references created with `Weak::new`.
+    ptr: Option<NonNull<ArcInner<T>>>,
+}
+
+// `repr(C)` so that the offset of the data only depends on its alignment.
+#[repr(C)]
+struct ArcInner<T: ?Sized> {
+    header: Header,
+
+    // Uninitialized while `Arc::new_cyclic` runs, and dropped or moved out
+    // once the strong count reaches zero.
+    data: T,
+}
+
+struct Header {
+    strong: AtomicUsize,
+
+    // One more than the number of `Weak`s, while any `Arc` remains. Set to
+    // `usize::MAX` while `Arc::get_mut` checks for uniqueness.
+    weak: AtomicUsize,
+
+    // The layout of the whole allocation, as the data may be gone by the time
+    // it is freed.
+    layout: Layout,
+
+    // Reports the allocation as leaked if it is never freed.
+    _track: Track<()>,
+}
+
+unsafe impl<T: ?Sized + Send + Sync> Send for Arc<T> {}
+unsafe impl<T: ?Sized + Send + Sync> Sync for Arc<T> {}
+unsafe impl<T: ?Sized + Send + Sync> Send for Weak<T> {}
+unsafe impl<T: ?Sized + Send + Sync> Sync for Weak<T> {}
+
+impl<T: ?Sized + RefUnwindSafe> UnwindSafe for Arc<T> {}
+
+impl<T> Arc<T> {
+    /// Construct a new `Arc<T>`.
+    #[track_caller]
+    pub fn new(data: T) -> Arc<T> {
+        let inner = ArcInner::allocate(1, Layout::new::<T>(), &data);
+
+        // SAFETY: The allocation is fresh and sized for `data`.
+        unsafe { ptr::addr_of_mut!((*inner.as_ptr()).data).write(data) };
+
+        Arc::from_inner(inner)
+    }
+
+    /// Construct a new `Arc<T>` while giving access to a `Weak<T>` to the
+    /// allocation, to build cyclic data structures.
+    ///
+    /// Upgrading the `Weak<T>` before `data_fn` returns yields `None`.
+    #[track_caller]
+    pub fn new_cyclic<F>(data_fn: F) -> Arc<T>
+    where
+        F: FnOnce(&Weak<T>) -> T,
+    {
+        let inner = ArcInner::allocate(0, Layout::new::<T>(), ptr::dangling());
+
+        // Stands in for the implicit weak reference held by the strong
+        // references, and frees the allocation if `data_fn` panics.
+        let weak = Weak { ptr: Some(inner) };
+        let data = data_fn(&weak);
+
+        // SAFETY: The strong count is zero, so nothing reads the data yet.
+        unsafe {
+            ptr::addr_of_mut!((*inner.as_ptr()).data).write(data);
+            inner.as_ref().header.strong.store(1, Ordering::Release);
+        }
+
+        mem::forget(weak);
+
+        Arc::from_inner(inner)
+    }
+
+    /// Construct a new `Pin<Arc<T>>`.
+    #[track_caller]
+    pub fn pin(data: T) -> Pin<Arc<T>> {
+        // SAFETY: The data is never moved out of a shared allocation.
+        unsafe { Pin::new_unchecked(Arc::new(data)) }
+    }
+
+    /// Return the inner value, if the `Arc` has exactly one strong reference.
+    #[track_caller]
+    pub fn try_unwrap(this: Arc<T>) -> Result<T, Arc<T>> {
+        if this
+            .inner()
+            .strong
+            .compare_exchange(1, 0, Ordering::Relaxed, Ordering::Relaxed)
+            .is_err()
+        {
+            return Err(this);
+        }
+
+        fence(Ordering::Acquire);
+
+        // SAFETY: This was the last strong reference.
+        Ok(unsafe { Arc::take_data(this) })
+    }
+
+    /// Return the inner value, if this is the last strong reference.
+    ///
+    /// Unlike [`Arc::try_unwrap`], the value is returned to exactly one of
+    /// several racing callers.
+    #[track_caller]
+    pub fn into_inner(this: Arc<T>) -> Option<T> {
+        if this.inner().strong.fetch_sub(1, Ordering::Release) != 1 {
+            mem::forget(this);
+            return None;
+        }
+
+        fence(Ordering::Acquire);
+
+        // SAFETY: This was the last strong reference.
+        Some(unsafe { Arc::take_data(this) })
+    }
+
+    // Move the data out of an `Arc` whose strong count has dropped to zero.
+    #[track_caller]
+    unsafe fn take_data(this: Arc<T>) -> T {
+        let this = ManuallyDrop::new(this);
+        let data = ptr::addr_of!((*this.ptr.as_ptr()).data).read();
+
+        // Release the implicit weak reference held by the strong references.
+        drop(Weak {
+            ptr: Some(this.ptr),
+        });
+
+        data
+    }
+}
+
+impl<T: ?Sized> Arc<T> {
+    /// Convert a `std::sync::Arc` into an `Arc`, which lets unsized values be
+    /// constructed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if other `std::sync::Arc` or `std::sync::Weak` pointers to the
+    /// same allocation exist.
+    #[track_caller]
+    pub fn from_std(mut std: std::sync::Arc<T>) -> Arc<T> {
+        assert!(
+            std::sync::Arc::get_mut(&mut std).is_some(),
+            "Arc provided to `from_std` is not unique"
+        );
+
+        let src = std::sync::Arc::into_raw(std);
+
+        // SAFETY: `src` is the only pointer to its data, which is moved out
+        // before the allocation is freed without dropping it.
+        unsafe {
+            let arc = Arc::from_inner(ArcInner::move_from(src));
+            drop(std::sync::Arc::from_raw(src as *const ManuallyDrop<T>));
+            arc
+        }
+    }
+
+    /// Create a new `Weak` pointer to this allocation.
+    #[track_caller]
+    pub fn downgrade(this: &Arc<T>) -> Weak<T> {
+        let weak = &this.inner().weak;
+        let mut current = weak.load(Ordering::Relaxed);
+
+        loop {
+            // Wait out a concurrent `get_mut` uniqueness check.
+            if current == usize::MAX {
+                hint::spin_loop();
+                current = weak.load(Ordering::Relaxed);
+                continue;
+            }
+
+            match weak.compare_exchange_weak(
+                current,
+                current + 1,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => {
+                    return Weak {
+                        ptr: Some(this.ptr),
+                    }
+                }
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
+    /// Get the number of strong (`Arc`) pointers to this allocation.
+    #[track_caller]
+    pub fn strong_count(this: &Arc<T>) -> usize {
+        this.inner().strong.load(Ordering::Relaxed)
+    }
+
+    /// Get the number of `Weak` pointers to this allocation.
+    #[track_caller]
+    pub fn weak_count(this: &Arc<T>) -> usize {
+        let count = this.inner().weak.load(Ordering::Acquire);
+
+        // A `get_mut` uniqueness check only happens without any `Weak`s.
+        if count == usize::MAX {
+            0
+        } else {
+            count - 1
+        }
+    }
+
+    /// Increment the strong reference count on the `Arc<T>` associated with
+    /// the provided pointer by one.
+    ///
+    /// # Safety
+    ///
+    /// The pointer must have been obtained through `Arc::into_raw`, and the
+    /// associated `Arc` instance must be valid for the duration of this
+    /// method.
+    #[track_caller]
+    pub unsafe fn increment_strong_count(ptr: *const T) {
+        let arc = ManuallyDrop::new(Arc::from_raw(ptr));
+        let _clone: ManuallyDrop<_> = arc.clone();
+    }
+
+    /// Decrement the strong reference count on the `Arc<T>` associated with
+    /// the provided pointer by one.
+    ///
+    /// # Safety
+    ///
+    /// The pointer must have been obtained through `Arc::into_raw`, and the
+    /// associated `Arc` instance must be valid when invoking this method.
+    #[track_caller]
+    pub unsafe fn decrement_strong_count(ptr: *const T) {
+        drop(Arc::from_raw(ptr));
+    }
+
+    /// Get a mutable reference to the inner value, if there are no other
+    /// `Arc` or `Weak` pointers to the same allocation.
+    #[track_caller]
+    pub fn get_mut(this: &mut Arc<T>) -> Option<&mut T> {
+        if this.is_unique() {
+            // SAFETY: No other pointer can observe the data.
+            Some(unsafe { Arc::get_mut_unchecked(this) })
+        } else {
+            None
+        }
+    }
+
+    /// Whether the two `Arc`s point to the same allocation.
+    pub fn ptr_eq(this: &Arc<T>, other: &Arc<T>) -> bool {
+        ptr::addr_eq(this.ptr.as_ptr(), other.ptr.as_ptr())
+    }
+
+    /// Consume the `Arc`, returning the wrapped pointer.
+    pub fn into_raw(this: Arc<T>) -> *const T {
+        let ptr = Arc::as_ptr(&this);
+        mem::forget(this);
+        ptr
+    }
+
+    /// Get a raw pointer to the data.
+    pub fn as_ptr(this: &Arc<T>) -> *const T {
+        // SAFETY: The allocation is live while `this` is.
+        unsafe { ptr::addr_of!((*this.ptr.as_ptr()).data) }
+    }
+
+    /// Construct an `Arc` from a raw pointer.
+    ///
+    /// # Safety
+    ///
+    /// The raw pointer must have been previously returned by a call to
+    /// [`Arc::into_raw`], and must only be converted back once.
+    pub unsafe fn from_raw(ptr: *const T) -> Arc<T> {
+        let offset = data_offset(mem::align_of_val(&*ptr));
+        let inner = (ptr as *mut ArcInner<T>).byte_sub(offset);
+
+        Arc::from_inner(NonNull::new_unchecked(inner))
+    }
+
+    fn from_inner(ptr: NonNull<ArcInner<T>>) -> Arc<T> {
+        Arc {
+            ptr,
+            phantom: PhantomData,
+        }
+    }
+
+    fn inner(&self) -> &Header {
+        // SAFETY: The allocation is live while any `Arc` is.
+        unsafe { &(*self.ptr.as_ptr()).header }
+    }
+
+    #[track_caller]
+    fn is_unique(&mut self) -> bool {
+        // Lock out `downgrade` while checking the strong count, as a new
+        // `Weak` could otherwise be upgraded behind our back.
+        if self
+            .inner()
+            .weak
+            .compare_exchange(1, usize::MAX, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+        {
+            let unique = self.inner().strong.load(Ordering::Acquire) == 1;
+            self.inner().weak.store(1, Ordering::Release);
+            unique
+        } else {
+            false
+        }
+    }
+
+    unsafe fn get_mut_unchecked(this: &mut Arc<T>) -> &mut T {
+        &mut (*this.ptr.as_ptr()).data
+    }
+}
+
+impl<T: Clone> Arc<T> {
+    /// Make a mutable reference into the given `Arc`, cloning the inner value
+    /// if other `Arc`s point to the same allocation.
+    ///
+    /// If only `Weak`s remain, the value is moved into a new allocation
+    /// instead, disassociating them.
+    #[track_caller]
+    pub fn make_mut(this: &mut Arc<T>) -> &mut T {
+        if this
+            .inner()
+            .strong
+            .compare_exchange(1, 0, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            *this = Arc::new((**this).clone());
+        } else if this.inner().weak.load(Ordering::Relaxed) != 1 {
+            // SAFETY: The strong count is now zero, so the data can be moved
+            // out, and `this` is overwritten without running its `Drop`.
+            unsafe {
+                let _weak = Weak {
+                    ptr: Some(this.ptr),
+                };
+                let data = ptr::addr_of!((*this.ptr.as_ptr()).data).read();
+
+                ptr::write(this, Arc::new(data));
+            }
+        } else {
+            this.inner().strong.store(1, Ordering::Release);
+        }
+
+        // SAFETY: `this` is now the only pointer to its allocation.
+        unsafe { Arc::get_mut_unchecked(this) }
+    }
+}
+
+impl<T> Weak<T> {
+    /// Construct a new `Weak<T>` without allocating. Upgrading it always
+    /// yields `None`.
+    pub fn new() -> Weak<T> {
+        Weak { ptr: None }
+    }
+}
+
+impl<T: ?Sized> Weak<T> {
+    /// Attempt to upgrade to an `Arc`, returning `None` if the inner value has
+    /// since been dropped.
+    #[track_caller]
+    pub fn upgrade(&self) -> Option<Arc<T>> {
+        let inner = self.inner()?;
+        let mut current = inner.strong.load(Ordering::Relaxed);
+
+        loop {
+            if current == 0 {
+                return None;
+            }
+
+            match inner.strong.compare_exchange_weak(
+                current,
+                current + 1,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return Some(Arc::from_inner(self.ptr?)),
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
+    /// Get the number of strong (`Arc`) pointers to this allocation.
+    #[track_caller]
+    pub fn strong_count(&self) -> usize {
+        self.inner()
+            .map_or(0, |inner| inner.strong.load(Ordering::Relaxed))
+    }
+
+    /// Get the number of `Weak` pointers to this allocation, or zero if no
+    /// strong pointers remain.
+    #[track_caller]
+    pub fn weak_count(&self) -> usize {
+        self.inner().map_or(0, |inner| {
+            let weak = inner.weak.load(Ordering::Acquire);
+            let strong = inner.strong.load(Ordering::Relaxed);
+
+            if strong == 0 {
+                0
+            } else {
+                weak - 1
+            }
+        })
+    }
+
+    /// Whether the two `Weak`s point to the same allocation, or were both
+    /// created with `Weak::new`.
+    pub fn ptr_eq(&self, other: &Weak<T>) -> bool {
+        match (self.ptr, other.ptr) {
+            (Some(a), Some(b)) => ptr::addr_eq(a.as_ptr(), b.as_ptr()),
+            (a, b) => a.is_none() && b.is_none(),
+        }
+    }
+
+    fn inner(&self) -> Option<&Header> {
+        // SAFETY: The allocation is live while any `Weak` to it is.
+        self.ptr.map(|ptr| unsafe { &(*ptr.as_ptr()).header })
+    }
+}
+
+impl<T: ?Sized> ArcInner<T> {
+    /// Allocate room for a value with the given layout, leaving the data
+    /// uninitialized. Only the pointer metadata of `like` is used.
+    #[track_caller]
+    fn allocate(strong: usize, value: Layout, like: *const T) -> NonNull<ArcInner<T>> {
+        let (layout, _) = Layout::new::<Header>().extend(value).unwrap();
+        let layout = layout.pad_to_align();
+
+        // SAFETY: The layout has a non-zero size, as it holds a `Header`.
+        let mem = unsafe { alloc::alloc(layout) };
+
+        if mem.is_null() {
+            alloc::handle_alloc_error(layout);
+        }
+
+        // Borrow the pointer metadata of `like`, replacing its address.
+        let mut inner = like as *mut ArcInner<T>;
+        // SAFETY: The address is the first word of any pointer.
+        unsafe { ptr::write(ptr::addr_of_mut!(inner).cast::<*mut u8>(), mem) };
+
+        // SAFETY: The allocation fits a `Header` at its start.
+        unsafe {
+            ptr::addr_of_mut!((*inner).header).write(Header {
+                strong: AtomicUsize::new(strong),
+                weak: AtomicUsize::new(1),
+                layout,
+                _track: Track::new(()),
+            });
+
+            NonNull::new_unchecked(inner)
+        }
+    }
+
+    /// Allocate with a strong count of one, moving the value `src` points to
+    /// into the allocation.
+    ///
+    /// # Safety
+    ///
+    /// `src` must point to a valid value, which must not be used or dropped
+    /// afterwards.
+    #[track_caller]
+    unsafe fn move_from(src: *const T) -> NonNull<ArcInner<T>> {
+        let inner = ArcInner::allocate(1, Layout::for_value(&*src), src);
+
+        ptr::copy_nonoverlapping(
+            src.cast::<u8>(),
+            ptr::addr_of_mut!((*inner.as_ptr()).data).cast::<u8>(),
+            mem::size_of_val(&*src),
+        );
+
+        inner
+    }
+}
+
+/// The offset of the data in an `ArcInner` for data aligned to `align`.
+fn data_offset(align: usize) -> usize {
+    let data = Layout::from_size_align(0, align).unwrap();
+    Layout::new::<Header>().extend(data).unwrap().1
+}
+
+impl<T: ?Sized> ops::Deref for Arc<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: The data is initialized while any `Arc` exists.
+        unsafe { &(*self.ptr.as_ptr()).data }
+    }
+}
+
+impl<T: ?Sized> Clone for Arc<T> {
+    #[track_caller]
+    fn clone(&self) -> Arc<T> {
+        self.inner().strong.fetch_add(1, Ordering::Relaxed);
+
+        Arc::from_inner(self.ptr)
+    }
+}
+
+impl<T: ?Sized> Drop for Arc<T> {
+    #[track_caller]
+    fn drop(&mut self) {
+        if self.inner().strong.fetch_sub(1, Ordering::Release) != 1 {
+            return;
+        }
+
+        fence(Ordering::Acquire);
+
+        // SAFETY: This was the last strong reference.
+        unsafe {
+            ptr::drop_in_place(ptr::addr_of_mut!((*self.ptr.as_ptr()).data));
+        }
+
+        drop(Weak {
+            ptr: Some(self.ptr),
+        });
+    }
+}
+
+impl<T: ?Sized> Clone for Weak<T> {
+    #[track_caller]
+    fn clone(&self) -> Weak<T> {
+        if let Some(inner) = self.inner() {
+            inner.weak.fetch_add(1, Ordering::Relaxed);
+        }
+
+        Weak { ptr: self.ptr }
+    }
+}
+
+impl<T: ?Sized> Drop for Weak<T> {
+    #[track_caller]
+    fn drop(&mut self) {
+        let Some(inner) = self.inner() else {
+            return;
+        };
+
+        if inner.weak.fetch_sub(1, Ordering::Release) != 1 {
+            return;
+        }
+
+        fence(Ordering::Acquire);
+
+        // SAFETY: No `Arc` or `Weak` remains, and the data has already been
+        // dropped or moved out.
+        unsafe {
+            let ptr = self.ptr.unwrap().as_ptr();
+            let layout = (*ptr).header.layout;
+
+            ptr::drop_in_place(ptr::addr_of_mut!((*ptr).header));
+            alloc::dealloc(ptr.cast(), layout);
+        }
+    }
+}
+
+impl<T: Default> Default for Arc<T> {
+    fn default() -> Arc<T> {
+        Arc::new(T::default())
+    }
+}
+
+impl<T> Default for Weak<T> {
+    fn default() -> Weak<T> {
+        Weak::new()
+    }
+}
+
+impl<T> From<T> for Arc<T> {
+    fn from(data: T) -> Arc<T> {
+        Arc::new(data)
+    }
+}
+
+impl<T: ?Sized> From<Box<T>> for Arc<T> {
+    #[track_caller]
+    fn from(data: Box<T>) -> Arc<T> {
+        let src = Box::into_raw(data);
+
+        // SAFETY: The data is moved out before the box is freed without
+        // dropping it.
+        unsafe {
+            let arc = Arc::from_inner(ArcInner::move_from(src));
+            drop(Box::from_raw(src as *mut ManuallyDrop<T>));
+            arc
+        }
+    }
+}
+
+impl From<String> for Arc<str> {
+    #[track_caller]
+    fn from(data: String) -> Arc<str> {
+        Arc::from(data.into_boxed_str())
+    }
+}
+
+impl From<&str> for Arc<str> {
+    #[track_caller]
+    fn from(data: &str) -> Arc<str> {
+        Arc::from(Box::<str>::from(data))
+    }
+}
+
+impl<T> From<Vec<T>> for Arc<[T]> {
+    #[track_caller]
+    fn from(data: Vec<T>) -> Arc<[T]> {
+        Arc::from(data.into_boxed_slice())
+    }
+}
+
+impl<T: Clone> From<&[T]> for Arc<[T]> {
+    #[track_caller]
+    fn from(data: &[T]) -> Arc<[T]> {
+        Arc::from(Box::<[T]>::from(data))
+    }
+}
+
+impl<T: ?Sized> AsRef<T> for Arc<T> {
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
+impl<T: ?Sized> Borrow<T> for Arc<T> {
+    fn borrow(&self) -> &T {
+        self
+    }
+}
+
+impl<T: ?Sized + PartialEq> PartialEq for Arc<T> {
+    fn eq(&self, other: &Arc<T>) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: ?Sized + Eq> Eq for Arc<T> {}
+
+impl<T: ?Sized + Hash> Hash for Arc<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for Arc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: ?Sized + fmt::Display> fmt::Display for Arc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for Weak<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("(Weak)")
+    }
+}
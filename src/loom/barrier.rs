@@ -0,0 +1,85 @@
+//! `Barrier` built from loom's `Mutex` and `Condvar`, as loom's own is a stub.
+
+use std::fmt;
+
+use loom::sync::{Condvar, Mutex};
+
+/// A barrier enabling multiple threads to synchronize the beginning of some
+/// computation, mirroring `std::sync::Barrier`.
+pub struct Barrier {
+    lock: Mutex<BarrierState>,
+    cvar: Condvar,
+    num_threads: usize,
+}
+
+struct BarrierState {
+    count: usize,
+    generation_id: usize,
+}
+
+/// Returned by [`Barrier::wait`] when all threads in the barrier have
+/// rendezvoused, mirroring `std::sync::BarrierWaitResult`.
+pub struct BarrierWaitResult(bool);
+
+impl Barrier {
+    /// Create a new barrier that can block a given number of threads.
+    #[track_caller]
+    pub fn new(n: usize) -> Barrier {
+        Barrier {
+            lock: Mutex::new(BarrierState {
+                count: 0,
+                generation_id: 0,
+            }),
+            cvar: Condvar::new(),
+            num_threads: n,
+        }
+    }
+
+    /// Block the current thread until all threads have rendezvoused here.
+    ///
+    /// A single (arbitrary) thread will receive a [`BarrierWaitResult`] that
+    /// returns `true` from [`BarrierWaitResult::is_leader`].
+    #[track_caller]
+    pub fn wait(&self) -> BarrierWaitResult {
+        let mut lock = self.lock.lock().unwrap();
+        let local_gen = lock.generation_id;
+
+        lock.count += 1;
+
+        if lock.count < self.num_threads {
+            while local_gen == lock.generation_id {
+                lock = self.cvar.wait(lock).unwrap();
+            }
+
+            BarrierWaitResult(false)
+        } else {
+            lock.count = 0;
+            lock.generation_id = lock.generation_id.wrapping_add(1);
+            self.cvar.notify_all();
+
+            BarrierWaitResult(true)
+        }
+    }
+}
+
+impl BarrierWaitResult {
+    /// Whether this thread is the "leader thread" for the call to
+    /// [`Barrier::wait`].
+    pub fn is_leader(&self) -> bool {
+        self.0
+    }
+}
+
+impl fmt::Debug for Barrier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Barrier").finish_non_exhaustive()
+    }
+}
+
+impl fmt::Debug for BarrierWaitResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BarrierWaitResult")
+            .field("is_leader", &self.is_leader())
+            .finish()
+    }
+}
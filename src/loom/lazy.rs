@@ -0,0 +1,40 @@
+//! Statics which are reset between model executions.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+use loom::lazy_static::Lazy;
+
+/// A value for use in a `static`, initialized once per loom execution and
+/// dropped at its end.
+pub struct Static<T: Sync>(Lazy<T>);
+
+impl<T: Sync> Static<T> {
+    /// Create a new static with the given initializing function.
+    pub const fn new(init: fn() -> T) -> Static<T> {
+        Static(Lazy {
+            init,
+            _p: PhantomData,
+        })
+    }
+}
+
+impl<T: Sync + 'static> Deref for Static<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: Loom keys the value by the address of the `Lazy` and keeps it
+        // alive for the rest of the execution, while the reference handed out
+        // here is bound to `&self`.
+        let lazy: &'static Lazy<T> = unsafe { &*(&self.0 as *const Lazy<T>) };
+
+        lazy.get()
+    }
+}
+
+impl<T: Sync + 'static + fmt::Debug> fmt::Debug for Static<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Static").field(&**self).finish()
+    }
+}
@@ -0,0 +1,109 @@
+//! The `loom` backend.
+//!
+//! Only items which also exist in the `std` and `shuttle` backends are
+//! exported, so that code compiling against one backend compiles against all
+//! of them.
+
+pub use loom::{lazy_static, thread_local};
+
+pub mod alloc;
+
+pub mod cell {
+    pub use loom::cell::{Cell, ConstPtr, MutPtr, UnsafeCell};
+}
+
+pub mod future {
+    pub use super::executor::{Executor, Task};
+    pub use loom::future::{block_on, AtomicWaker};
+}
+
+pub mod hint {
+    pub use loom::hint::{spin_loop, unreachable_unchecked};
+}
+
+pub mod sync {
+    pub use super::arc::{Arc, Weak};
+    pub use super::barrier::{Barrier, BarrierWaitResult};
+    pub use super::once::{Once, OnceState};
+    pub use super::once_lock::{LazyLock, OnceLock};
+    pub use loom::sync::{
+        Condvar, LockResult, Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard,
+        TryLockResult, WaitTimeoutResult,
+    };
+
+    pub mod atomic {
+        pub use loom::sync::atomic::{
+            fence, AtomicBool, AtomicI16, AtomicI32, AtomicI64, AtomicI8, AtomicIsize, AtomicPtr,
+            AtomicU16, AtomicU32, AtomicU64, AtomicU8, AtomicUsize, Ordering,
+        };
+    }
+
+    pub mod mpsc {
+        pub use loom::sync::mpsc::{channel, Receiver, Sender};
+    }
+}
+
+pub mod thread {
+    pub use super::scope::{scope, Scope, ScopedJoinHandle};
+    pub use loom::thread::{
+        current, panicking, park, spawn, yield_now, AccessError, Builder, JoinHandle, LocalKey,
+        Thread, ThreadId,
+    };
+}
+
+mod arc;
+mod barrier;
+#[path = "../imp/executor.rs"]
+mod executor;
+mod lazy;
+mod once;
+mod once_lock;
+mod scope;
+
+pub use self::lazy::Static;
+
+use crate::model::Builder;
+
+/// Run the model closure, exploring every interleaving with loom.
+pub fn model<F>(f: F)
+where
+    F: Fn() + Sync + Send + 'static,
+{
+    check(&new_builder(), f)
+}
+
+pub(crate) fn new_builder() -> Builder {
+    let loom = loom::model::Builder::new();
+
+    Builder {
+        max_threads: loom.max_threads,
+        max_branches: loom.max_branches,
+        max_permutations: loom.max_permutations,
+        max_duration: loom.max_duration,
+        preemption_bound: loom.preemption_bound,
+        checkpoint_file: loom.checkpoint_file,
+        checkpoint_interval: loom.checkpoint_interval,
+        location: loom.location,
+        log: loom.log,
+        ..Builder::base()
+    }
+}
+
+pub(crate) fn check<F>(builder: &Builder, f: F)
+where
+    F: Fn() + Sync + Send + 'static,
+{
+    let mut loom = loom::model::Builder::new();
+
+    loom.max_threads = builder.max_threads;
+    loom.max_branches = builder.max_branches;
+    loom.max_permutations = builder.max_permutations;
+    loom.max_duration = builder.max_duration;
+    loom.preemption_bound = builder.preemption_bound;
+    loom.checkpoint_file = builder.checkpoint_file.clone();
+    loom.checkpoint_interval = builder.checkpoint_interval;
+    loom.location = builder.location;
+    loom.log = builder.log;
+
+    loom.check(move || crate::tracked::execution(&f))
+}
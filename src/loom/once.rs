@@ -0,0 +1,131 @@
+//! `Once` built from loom's `Mutex` and atomics, which loom lacks.
+
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::PoisonError;
+
+use loom::sync::atomic::{AtomicU8, Ordering};
+use loom::sync::Mutex;
+
+const INCOMPLETE: u8 = 0;
+const POISONED: u8 = 1;
+const COMPLETE: u8 = 2;
+
+/// A synchronization primitive which can be used to run a one-time
+/// initialization, mirroring `std::sync::Once`.
+pub struct Once {
+    state: AtomicU8,
+    lock: Mutex<()>,
+}
+
+/// State yielded to [`Once::call_once_force`]'s closure, mirroring
+/// `std::sync::OnceState`.
+pub struct OnceState {
+    poisoned: bool,
+}
+
+impl Once {
+    /// Create a new `Once` value.
+    #[track_caller]
+    pub fn new() -> Once {
+        Once {
+            state: AtomicU8::new(INCOMPLETE),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Perform an initialization routine once and only once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a previous initialization routine panicked, poisoning this
+    /// `Once`.
+    #[track_caller]
+    pub fn call_once<F: FnOnce()>(&self, f: F) {
+        if self.is_completed() {
+            return;
+        }
+
+        let mut f = Some(f);
+        self.call(false, &mut |_| f.take().unwrap()());
+    }
+
+    /// Perform an initialization routine once and only once, ignoring
+    /// poisoning.
+    ///
+    /// The closure can inspect whether a previous initialization routine
+    /// panicked through [`OnceState::is_poisoned`].
+    #[track_caller]
+    pub fn call_once_force<F: FnOnce(&OnceState)>(&self, f: F) {
+        if self.is_completed() {
+            return;
+        }
+
+        let mut f = Some(f);
+        self.call(true, &mut |state| f.take().unwrap()(state));
+    }
+
+    /// Whether some initialization routine has completed successfully.
+    #[track_caller]
+    pub fn is_completed(&self) -> bool {
+        self.state.load(Ordering::Acquire) == COMPLETE
+    }
+
+    #[track_caller]
+    fn call(&self, ignore_poisoning: bool, f: &mut dyn FnMut(&OnceState)) {
+        // loom's `Mutex` cannot be locked again once a panic unwinds through
+        // its guard, so panics are only raised with the lock released.
+        let lock = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let result = match self.state.load(Ordering::Acquire) {
+            COMPLETE => return,
+            POISONED if !ignore_poisoning => None,
+            state => Some(panic::catch_unwind(AssertUnwindSafe(|| {
+                f(&OnceState {
+                    poisoned: state == POISONED,
+                })
+            }))),
+        };
+
+        let state = match result {
+            Some(Ok(())) => COMPLETE,
+            _ => POISONED,
+        };
+
+        self.state.store(state, Ordering::Release);
+        drop(lock);
+
+        match result {
+            None => panic!("Once instance has previously been poisoned"),
+            Some(Err(payload)) => panic::resume_unwind(payload),
+            Some(Ok(())) => {}
+        }
+    }
+}
+
+impl OnceState {
+    /// Whether the associated `Once` was poisoned prior to this invocation.
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned
+    }
+}
+
+impl Default for Once {
+    fn default() -> Once {
+        Once::new()
+    }
+}
+
+impl fmt::Debug for Once {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Once").finish_non_exhaustive()
+    }
+}
+
+impl fmt::Debug for OnceState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OnceState")
+            .field("poisoned", &self.poisoned)
+            .finish()
+    }
+}
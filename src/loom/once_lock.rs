@@ -0,0 +1,196 @@
+//! `OnceLock` and `LazyLock` built on the backend's `Once` and `UnsafeCell`,
+//! which neither loom nor shuttle provide.
+//!
+//! Shared between the `loom` and `shuttle` backends, so that every access to
+//! the value goes through the model.
+
+use std::fmt;
+use std::ops::Deref;
+
+use super::cell::UnsafeCell;
+use super::sync::Once;
+
+/// A synchronization primitive which can be written to only once, mirroring
+/// `std::sync::OnceLock`.
+pub struct OnceLock<T> {
+    once: Once,
+    value: UnsafeCell<Option<T>>,
+}
+
+/// A value which is initialized on the first access, mirroring
+/// `std::sync::LazyLock`.
+pub struct LazyLock<T, F = fn() -> T> {
+    cell: OnceLock<T>,
+    init: UnsafeCell<Option<F>>,
+}
+
+impl<T> OnceLock<T> {
+    /// Create a new empty cell.
+    #[track_caller]
+    pub fn new() -> OnceLock<T> {
+        OnceLock {
+            once: Once::new(),
+            value: UnsafeCell::new(None),
+        }
+    }
+
+    /// Get a reference to the underlying value, if initialized.
+    #[track_caller]
+    pub fn get(&self) -> Option<&T> {
+        if !self.once.is_completed() {
+            return None;
+        }
+
+        // SAFETY: The value is never written again once `once` has completed,
+        // and `is_completed` synchronizes with that write.
+        self.value.with(|value| unsafe { (*value).as_ref() })
+    }
+
+    /// Get a mutable reference to the underlying value, if initialized.
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        // SAFETY: `&mut self` guarantees exclusive access.
+        self.value.with_mut(|value| unsafe { (*value).as_mut() })
+    }
+
+    /// Initialize the cell with `value`, returning it back if the cell was
+    /// already initialized.
+    #[track_caller]
+    pub fn set(&self, value: T) -> Result<(), T> {
+        let mut value = Some(value);
+        self.get_or_init(|| value.take().unwrap());
+
+        match value {
+            None => Ok(()),
+            Some(value) => Err(value),
+        }
+    }
+
+    /// Get the contents of the cell, initializing it with `f` if empty.
+    ///
+    /// If `f` panics, the panic is propagated and the cell remains
+    /// uninitialized.
+    #[track_caller]
+    pub fn get_or_init<F: FnOnce() -> T>(&self, f: F) -> &T {
+        if let Some(value) = self.get() {
+            return value;
+        }
+
+        // `Once` is only poisoned by a panicking `f`, which leaves the cell
+        // empty, so a later caller may simply retry.
+        self.once.call_once_force(|_| {
+            let value = f();
+
+            // SAFETY: `once` guarantees exclusive access while running, and no
+            // reader looks at the value before it has completed.
+            self.value.with_mut(|slot| unsafe { *slot = Some(value) });
+        });
+
+        self.get().unwrap()
+    }
+
+    /// Consume the cell, returning the wrapped value if initialized.
+    pub fn into_inner(mut self) -> Option<T> {
+        self.take()
+    }
+
+    /// Take the value out of the cell, leaving it uninitialized.
+    pub fn take(&mut self) -> Option<T> {
+        self.once = Once::new();
+
+        // SAFETY: `&mut self` guarantees exclusive access.
+        self.value.with_mut(|value| unsafe { (*value).take() })
+    }
+}
+
+// SAFETY: The value is written once, under `once`, and shared afterwards.
+unsafe impl<T: Send + Sync> Sync for OnceLock<T> {}
+
+impl<T> Default for OnceLock<T> {
+    fn default() -> OnceLock<T> {
+        OnceLock::new()
+    }
+}
+
+impl<T> From<T> for OnceLock<T> {
+    fn from(value: T) -> OnceLock<T> {
+        let cell = OnceLock::new();
+        let _ = cell.set(value);
+        cell
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for OnceLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_tuple("OnceLock");
+
+        match self.get() {
+            Some(value) => d.field(value),
+            None => d.field(&format_args!("<uninit>")),
+        };
+
+        d.finish()
+    }
+}
+
+impl<T, F: FnOnce() -> T> LazyLock<T, F> {
+    /// Create a new lazy value with the given initializing function.
+    #[track_caller]
+    pub fn new(f: F) -> LazyLock<T, F> {
+        LazyLock {
+            cell: OnceLock::new(),
+            init: UnsafeCell::new(Some(f)),
+        }
+    }
+
+    /// Force the evaluation of this lazy value and return a reference to the
+    /// result.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a previous initialization panicked, poisoning this value.
+    #[track_caller]
+    pub fn force(this: &LazyLock<T, F>) -> &T {
+        this.cell.get_or_init(|| {
+            // SAFETY: Only ever accessed from within `get_or_init`, which runs
+            // at most one initializer at a time.
+            let init = this.init.with_mut(|init| unsafe { (*init).take() });
+
+            match init {
+                Some(init) => init(),
+                None => panic!("LazyLock instance has previously been poisoned"),
+            }
+        })
+    }
+}
+
+// SAFETY: The initializer is only run by one thread, after which the value is
+// shared.
+unsafe impl<T: Send + Sync, F: Send> Sync for LazyLock<T, F> {}
+
+impl<T, F: FnOnce() -> T> Deref for LazyLock<T, F> {
+    type Target = T;
+
+    #[track_caller]
+    fn deref(&self) -> &T {
+        LazyLock::force(self)
+    }
+}
+
+impl<T: Default> Default for LazyLock<T> {
+    fn default() -> LazyLock<T> {
+        LazyLock::new(T::default)
+    }
+}
+
+impl<T: fmt::Debug, F> fmt::Debug for LazyLock<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_tuple("LazyLock");
+
+        match self.cell.get() {
+            Some(value) => d.field(value),
+            None => d.field(&format_args!("<uninit>")),
+        };
+
+        d.finish()
+    }
+}
@@ -0,0 +1,168 @@
+//! Scoped threads built on `loom::thread::spawn`, which loom lacks.
+//!
+//! The bookkeeping below uses `std` primitives on purpose: they are never held
+//! across a loom operation. The happens-before edges between a scoped thread
+//! and its joiner come from loom's own `JoinHandle::join`, or from a loom
+//! atomic when the scope joined the thread first.
+
+use std::marker::PhantomData;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+use std::{fmt, mem};
+
+use loom::sync::atomic::{AtomicBool, Ordering};
+use loom::thread::{self, JoinHandle, Thread};
+
+type Handle = Arc<Mutex<Option<JoinHandle<()>>>>;
+
+/// A scope to spawn scoped threads in, mirroring `std::thread::Scope`.
+pub struct Scope<'scope, 'env: 'scope> {
+    handles: Mutex<Vec<Handle>>,
+    scope: PhantomData<&'scope mut &'scope ()>,
+    env: PhantomData<&'env mut &'env ()>,
+}
+
+/// An owned permission to join on a scoped thread, mirroring
+/// `std::thread::ScopedJoinHandle`.
+pub struct ScopedJoinHandle<'scope, T> {
+    handle: Handle,
+    result: Arc<Mutex<Option<T>>>,
+    finished: Arc<AtomicBool>,
+    thread: Thread,
+    scope: PhantomData<&'scope ()>,
+}
+
+/// Create a scope for spawning scoped threads, mirroring `std::thread::scope`.
+///
+/// All threads spawned within the scope which haven't been manually joined
+/// are joined before this function returns.
+#[track_caller]
+pub fn scope<'env, F, T>(f: F) -> T
+where
+    F: for<'scope> FnOnce(&'scope Scope<'scope, 'env>) -> T,
+{
+    let scope = Scope {
+        handles: Mutex::new(Vec::new()),
+        scope: PhantomData,
+        env: PhantomData,
+    };
+
+    // Scoped threads may borrow from the stack being unwound, so they must be
+    // joined even if `f` panics.
+    let result = panic::catch_unwind(AssertUnwindSafe(|| f(&scope)));
+
+    scope.join_all();
+
+    match result {
+        Ok(result) => result,
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
+
+impl<'scope, 'env> Scope<'scope, 'env> {
+    /// Spawn a new thread within the scope, mirroring
+    /// `std::thread::Scope::spawn`.
+    ///
+    /// Unlike `std`, a panicking thread fails the model as soon as it panics,
+    /// like any other loom thread.
+    #[track_caller]
+    pub fn spawn<F, T>(&'scope self, f: F) -> ScopedJoinHandle<'scope, T>
+    where
+        F: FnOnce() -> T + Send + 'scope,
+        T: Send + 'scope,
+    {
+        let result = Arc::new(Mutex::new(None));
+        let slot = Arc::clone(&result);
+        let finished = Arc::new(AtomicBool::new(false));
+        let done = Arc::clone(&finished);
+
+        let main: Box<dyn FnOnce() + Send + 'scope> = Box::new(move || {
+            let value = f();
+            *slot.lock().unwrap() = Some(value);
+            done.store(true, Ordering::Release);
+        });
+
+        // SAFETY: Every thread spawned in the scope is joined before `scope`
+        // returns, so nothing borrowed for `'scope` is used after it ends.
+        let main: Box<dyn FnOnce() + Send + 'static> = unsafe { mem::transmute(main) };
+
+        let handle = thread::spawn(main);
+        let thread = handle.thread().clone();
+        let handle = Arc::new(Mutex::new(Some(handle)));
+
+        self.handles.lock().unwrap().push(Arc::clone(&handle));
+
+        ScopedJoinHandle {
+            handle,
+            result,
+            finished,
+            thread,
+            scope: PhantomData,
+        }
+    }
+
+    #[track_caller]
+    fn join_all(&self) {
+        // Scoped threads may spawn further threads into the scope while it is
+        // being joined.
+        loop {
+            let handles = mem::take(&mut *self.handles.lock().unwrap());
+
+            if handles.is_empty() {
+                break;
+            }
+
+            for handle in handles {
+                let handle = handle.lock().unwrap().take();
+
+                if let Some(handle) = handle {
+                    // Loom fails the model on panic, so this is always `Ok`.
+                    let _ = handle.join();
+                }
+            }
+        }
+    }
+}
+
+impl<'scope, T> ScopedJoinHandle<'scope, T> {
+    /// Wait for the thread to finish, mirroring
+    /// `std::thread::ScopedJoinHandle::join`.
+    #[track_caller]
+    pub fn join(self) -> std::thread::Result<T> {
+        let handle = self.handle.lock().unwrap().take();
+
+        if let Some(handle) = handle {
+            handle.join()?;
+        }
+
+        // The scope may have taken the handle first, in which case the result
+        // shows up once the thread has finished.
+        while !self.finished.load(Ordering::Acquire) {
+            thread::yield_now();
+        }
+
+        Ok(self.result.lock().unwrap().take().unwrap())
+    }
+
+    /// Get a handle to the underlying thread.
+    pub fn thread(&self) -> &Thread {
+        &self.thread
+    }
+
+    /// Check if the thread has finished running its main function.
+    pub fn is_finished(&self) -> bool {
+        self.result.lock().unwrap().is_some()
+    }
+}
+
+impl fmt::Debug for Scope<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Scope").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Debug for ScopedJoinHandle<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScopedJoinHandle").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,180 @@
+//! Model configuration shared by both backends.
+//!
+//! [`Builder`] mirrors `loom::model::Builder`. When `--cfg loom` is set, the
+//! configuration is forwarded to loom as is. With the `shuttle` feature, the
+//! model closure is run [`Builder::iterations`] times under shuttle's random
+//! or PCT scheduler. Otherwise, it is run on real threads up to
+//! [`Builder::iterations`] times. In both cases, no further iterations are
+//! started once [`Builder::max_duration`] has elapsed, and with `std`, an
+//! execution running past [`Builder::timeout`] fails. The remaining options
+//! only affect `loom`.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::Outcomes;
+
+/// Configure a model.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct Builder {
+    /// Max number of threads to check as part of the execution.
+    ///
+    /// Ignored by `std` and `shuttle`.
+    pub max_threads: usize,
+
+    /// Maximum number of thread switches per permutation.
+    ///
+    /// Ignored by `std` and `shuttle`.
+    pub max_branches: usize,
+
+    /// Maximum number of permutations to explore.
+    ///
+    /// Ignored by `std` and `shuttle`.
+    pub max_permutations: Option<usize>,
+
+    /// Maximum amount of time to spend on checking.
+    ///
+    /// With `std` and `shuttle`, no further iterations are started once this
+    /// has elapsed.
+    pub max_duration: Option<Duration>,
+
+    /// Maximum number of thread preemptions to explore.
+    ///
+    /// Ignored by `std` and `shuttle`.
+    pub preemption_bound: Option<usize>,
+
+    /// File used to store and load the progress of an exhaustive check.
+    ///
+    /// Ignored by `std` and `shuttle`.
+    pub checkpoint_file: Option<PathBuf>,
+
+    /// How often to write the checkpoint file.
+    ///
+    /// Ignored by `std` and `shuttle`.
+    pub checkpoint_interval: usize,
+
+    /// Capture locations on each loom operation.
+    ///
+    /// loomy's own wrappers are `#[track_caller]`, so causality violations then
+    /// name the caller's source location. Under `loom`, defaults to whether
+    /// `LOOM_LOCATION` is set.
+    ///
+    /// Ignored by `std` and `shuttle`.
+    pub location: bool,
+
+    /// Log execution output to stdout.
+    ///
+    /// Ignored by `std` and `shuttle`.
+    pub log: bool,
+
+    /// Number of times the model closure is run with `std` or `shuttle`.
+    ///
+    /// With `std`, accesses are perturbed with randomized yields when this is
+    /// greater than one. Ignored by `loom`, which explores executions
+    /// exhaustively instead.
+    ///
+    /// Defaults to `LOOMY_ITERATIONS` environment variable, or `1` with `std`
+    /// and `1000` with `shuttle`. Checking a model panics if this is zero.
+    pub iterations: usize,
+
+    /// Use shuttle's PCT scheduler with this bug depth instead of its random
+    /// scheduler.
+    ///
+    /// Ignored by `loom` and `std`.
+    pub pct_depth: Option<usize>,
+
+    /// Maximum amount of time a single execution may run with `std`.
+    ///
+    /// Each execution then runs on a thread of its own. Once it runs for
+    /// longer, it is considered hung: its threads are leaked, and the model
+    /// panics with the state of every thread of the execution spawned through
+    /// `loomy::thread`, including the loomy primitive it is blocked on. Other
+    /// tests keep running. Ignored by `loom` and `shuttle`, which detect
+    /// deadlocks themselves.
+    ///
+    /// Defaults to `LOOMY_TIMEOUT` environment variable, in seconds, if set.
+    pub timeout: Option<Duration>,
+}
+
+impl Builder {
+    /// Create a new `Builder` instance with default values.
+    ///
+    /// When `--cfg loom` is set, the defaults are taken from
+    /// `loom::model::Builder::new`, which reads the `LOOM_*` environment
+    /// variables.
+    pub fn new() -> Builder {
+        crate::imp::new_builder()
+    }
+
+    /// Defaults shared by every backend, before any environment variables are
+    /// read.
+    pub(crate) fn base() -> Builder {
+        Builder {
+            max_threads: 5,
+            max_branches: 1_000,
+            max_permutations: None,
+            max_duration: None,
+            preemption_bound: None,
+            checkpoint_file: None,
+            checkpoint_interval: 20_000,
+            location: false,
+            log: false,
+            iterations: 1,
+            pct_depth: None,
+            timeout: None,
+        }
+    }
+
+    /// The number of iterations to run with `std` or `shuttle`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if it is zero, which would pass without running the model.
+    #[cfg(not(loom))]
+    pub(crate) fn checked_iterations(&self) -> usize {
+        assert!(
+            self.iterations != 0,
+            "loomy: the number of iterations must be at least 1, as the model would never run"
+        );
+
+        self.iterations
+    }
+
+    /// Set the checkpoint file.
+    pub fn checkpoint_file(&mut self, file: &str) -> &mut Self {
+        self.checkpoint_file = Some(file.into());
+        self
+    }
+
+    /// Check the provided model.
+    pub fn check<F>(&self, f: F)
+    where
+        F: Fn() + Sync + Send + 'static,
+    {
+        crate::imp::check(self, f)
+    }
+
+    /// Check the provided model, returning every outcome passed to
+    /// [`observe`](crate::observe) across all executions.
+    pub fn outcomes<F>(&self, f: F) -> Outcomes
+    where
+        F: Fn() + Sync + Send + 'static,
+    {
+        crate::observe::collect(|| self.check(f))
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Number of iterations requested through `LOOMY_ITERATIONS`, if any.
+#[cfg(not(loom))]
+pub(crate) fn env_iterations() -> Option<usize> {
+    std::env::var("LOOMY_ITERATIONS")
+        .ok()
+        .map(|v| v.parse().expect("invalid value for `LOOMY_ITERATIONS`"))
+}
@@ -0,0 +1,187 @@
+//! Outcome collection across model executions.
+//!
+//! Observations are recorded in a process-wide registry outside of the model,
+//! so recording a value is never an interleaving point. Each `outcomes` call
+//! gets its own session in the registry, which only the threads running its
+//! model record into.
+
+use std::any::Any;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::context;
+use crate::model::Builder;
+
+type Sets = HashMap<&'static str, Box<dyn Set>>;
+
+/// Observations of each running `outcomes` call, by session id.
+static SESSIONS: Mutex<BTreeMap<u64, Sets>> = Mutex::new(BTreeMap::new());
+
+/// Hands out session ids.
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Record `value` as an outcome under `label`.
+///
+/// Values are collected across every execution of a model run through
+/// [`outcomes`] or [`Builder::outcomes`]. Outside of those, this does nothing,
+/// as is the case under `std` on threads not spawned through `loomy::thread`.
+///
+/// # Panics
+///
+/// Panics if `label` was already observed with a different type.
+pub fn observe<T>(label: &'static str, value: T)
+where
+    T: Ord + fmt::Debug + Send + 'static,
+{
+    let Some(session) = context::with(|context| context.session) else {
+        return;
+    };
+
+    let mut sessions = lock(&SESSIONS);
+
+    let Some(sets) = sessions.get_mut(&session) else {
+        return;
+    };
+
+    let set = sets
+        .entry(label)
+        .or_insert_with(|| Box::new(BTreeSet::<T>::new()));
+
+    match set.as_any_mut().downcast_mut::<BTreeSet<T>>() {
+        Some(set) => {
+            set.insert(value);
+        }
+        None => {
+            drop(sessions);
+            panic!("loomy: outcome `{label}` observed with different types");
+        }
+    }
+}
+
+/// Run the model closure like [`model`](crate::model), returning every outcome
+/// passed to [`observe`] across all executions.
+///
+/// This asserts that each outcome is actually reached, rather than merely
+/// allowed:
+///
+/// ```rust
+/// use std::collections::BTreeSet;
+///
+/// use loomy::sync::atomic::{AtomicUsize, Ordering};
+/// use loomy::sync::Arc;
+/// use loomy::thread;
+///
+/// let outcomes = loomy::outcomes(|| {
+///     let n = Arc::new(AtomicUsize::new(0));
+///     let n2 = Arc::clone(&n);
+///
+///     let t = thread::spawn(move || n2.store(1, Ordering::SeqCst));
+///     loomy::observe("n", n.load(Ordering::SeqCst));
+///     t.join().unwrap();
+/// });
+///
+/// // `loom` explores every interleaving, so both outcomes are reached.
+/// if cfg!(loom) {
+///     assert_eq!(outcomes.get::<usize>("n"), BTreeSet::from([0, 1]));
+/// }
+/// ```
+///
+/// Under `std` the closure runs `LOOMY_ITERATIONS` times, so which outcomes
+/// are reached is down to the OS scheduler.
+pub fn outcomes<F>(f: F) -> Outcomes
+where
+    F: Fn() + Sync + Send + 'static,
+{
+    Builder::new().outcomes(f)
+}
+
+/// Outcomes recorded with [`observe`], returned by [`outcomes`].
+pub struct Outcomes {
+    sets: Sets,
+}
+
+impl Outcomes {
+    /// Get the distinct values observed under `label`, which is empty if none
+    /// were.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `label` was observed with a type other than `T`.
+    pub fn get<T>(&self, label: &str) -> BTreeSet<T>
+    where
+        T: Ord + Clone + 'static,
+    {
+        match self.sets.get(label) {
+            None => BTreeSet::new(),
+            Some(set) => match set.as_any().downcast_ref::<BTreeSet<T>>() {
+                Some(set) => set.clone(),
+                None => panic!("loomy: outcome `{label}` was observed with a different type"),
+            },
+        }
+    }
+
+    /// Iterate over every label observed at least once.
+    pub fn labels(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.sets.keys().copied()
+    }
+}
+
+impl fmt::Debug for Outcomes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(&self.sets).finish()
+    }
+}
+
+/// Run `f`, collecting every observation made meanwhile.
+pub(crate) fn collect(f: impl FnOnce()) -> Outcomes {
+    struct Reset {
+        id: u64,
+        previous: Option<u64>,
+    }
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            context::update(|context| context.session = self.previous);
+            lock(&SESSIONS).remove(&self.id);
+        }
+    }
+
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+
+    lock(&SESSIONS).insert(id, Sets::new());
+
+    // Stop collecting even if the model fails.
+    let _reset = Reset {
+        id,
+        previous: context::update(|context| context.session.replace(id)),
+    };
+
+    f();
+
+    let sets = lock(&SESSIONS).remove(&id).unwrap_or_default();
+
+    Outcomes { sets }
+}
+
+/// A type-erased set of observed values.
+trait Set: fmt::Debug + Send {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Ord + fmt::Debug + Send + 'static> Set for BTreeSet<T> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // Observations are whole inserts, so a panic cannot leave them torn.
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
@@ -0,0 +1,147 @@
+//! Generating the shape of concurrent tests with
+//! [proptest](https://docs.rs/proptest).
+//!
+//! A [`Scenario`] is a list of operations for each thread to run. Generate them
+//! with [`scenario`], and run each generated scenario under
+//! [`model`](crate::model) with [`check`]. Failing scenarios are shrunk to as
+//! few threads and operations as possible.
+//!
+//! ```rust
+//! use loomy::proptest::{self, scenario};
+//! use loomy::sync::atomic::{AtomicUsize, Ordering};
+//!
+//! proptest::check(scenario(1..10usize, 1..3, 0..3), |scenario| {
+//!     let n = AtomicUsize::new(0);
+//!
+//!     scenario.run(|_, &op| {
+//!         n.fetch_add(op, Ordering::Relaxed);
+//!     });
+//!
+//!     let sum = scenario.threads().iter().flatten().sum::<usize>();
+//!     assert_eq!(n.load(Ordering::Relaxed), sum);
+//! });
+//! ```
+//!
+//! Every scenario is explored in full under `loom`, so consider lowering
+//! `PROPTEST_CASES` or [`Config::cases`] there.
+
+use std::fmt;
+use std::sync::Arc;
+
+use ::proptest::collection::{self, SizeRange};
+use ::proptest::strategy::Strategy;
+use ::proptest::test_runner::{Config, TestCaseError, TestError, TestRunner};
+
+use crate::model::Builder;
+
+/// The operations run by each thread of a concurrent test.
+#[derive(Clone)]
+pub struct Scenario<Op> {
+    threads: Vec<Vec<Op>>,
+}
+
+impl<Op> Scenario<Op> {
+    /// Create a scenario running each list of operations on its own thread.
+    pub fn new(threads: Vec<Vec<Op>>) -> Scenario<Op> {
+        Scenario { threads }
+    }
+
+    /// Get the operations run by each thread.
+    pub fn threads(&self) -> &[Vec<Op>] {
+        &self.threads
+    }
+
+    /// Run every thread's operations in order on a thread of its own, calling
+    /// `f` with the index of the thread and each operation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any call to `f` panicked.
+    pub fn run<F>(&self, f: F)
+    where
+        Op: Sync,
+        F: Fn(usize, &Op) + Sync,
+    {
+        crate::__run_threads(self.threads.len(), |i| {
+            self.threads[i].iter().for_each(|op| f(i, op));
+        });
+    }
+}
+
+impl<Op: fmt::Debug> fmt::Debug for Scenario<Op> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_map();
+
+        for (i, ops) in self.threads.iter().enumerate() {
+            list.entry(&format_args!("thread {i}"), ops);
+        }
+
+        list.finish()
+    }
+}
+
+/// A strategy generating scenarios with a number of threads in `threads`, each
+/// running a number of operations in `ops` generated by `op`.
+///
+/// Scenarios shrink by dropping threads and operations, then by shrinking the
+/// operations themselves.
+///
+/// # Panics
+///
+/// Panics if `threads` allows zero threads.
+pub fn scenario<S>(
+    op: S,
+    threads: impl Into<SizeRange>,
+    ops: impl Into<SizeRange>,
+) -> impl Strategy<Value = Scenario<S::Value>>
+where
+    S: Strategy,
+{
+    let threads = threads.into();
+
+    assert!(
+        threads.start() > 0,
+        "loomy: scenarios need at least one thread"
+    );
+
+    collection::vec(collection::vec(op, ops), threads).prop_map(Scenario::new)
+}
+
+/// Run `f` under [`model`](crate::model) for scenarios generated by
+/// `strategy`, panicking with a minimal failing scenario if any fails.
+pub fn check<S, Op, F>(strategy: S, f: F)
+where
+    S: Strategy<Value = Scenario<Op>>,
+    Op: fmt::Debug + Send + Sync + 'static,
+    F: Fn(&Scenario<Op>) + Sync + Send + 'static,
+{
+    check_with(&Builder::new(), Config::default(), strategy, f);
+}
+
+/// Like [`check`], configuring the model with `builder` and proptest with
+/// `config`.
+pub fn check_with<S, Op, F>(builder: &Builder, config: Config, strategy: S, f: F)
+where
+    S: Strategy<Value = Scenario<Op>>,
+    Op: fmt::Debug + Send + Sync + 'static,
+    F: Fn(&Scenario<Op>) + Sync + Send + 'static,
+{
+    let f = Arc::new(f);
+
+    let result = TestRunner::new(config).run(&strategy, |scenario| {
+        let f = Arc::clone(&f);
+
+        // `proptest` turns a panicking model into a failure, shrinking it.
+        builder.check(move || f(&scenario));
+
+        Ok::<(), TestCaseError>(())
+    });
+
+    match result {
+        Ok(()) => {}
+        Err(TestError::Fail(reason, scenario)) => {
+            panic!("loomy: scenario failed: {reason}\nminimal failing scenario: {scenario:#?}")
+        }
+        Err(TestError::Abort(reason)) => panic!("loomy: scenario generation aborted: {reason}"),
+    }
+}
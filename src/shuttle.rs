@@ -0,0 +1,121 @@
+//! The `shuttle` backend.
+//!
+//! Only items which also exist in the `std` and `loom` backends are exported,
+//! so that code compiling against one backend compiles against all of them.
+
+use shuttle::sync::atomic as raw_atomic;
+
+#[path = "imp/alloc.rs"]
+pub mod alloc;
+
+// Shuttle does not track `UnsafeCell` accesses, so the `std` wrapper is reused
+// as is.
+#[path = "imp/cell.rs"]
+pub mod cell;
+
+#[cfg(feature = "checked")]
+#[path = "imp/checked.rs"]
+mod checked;
+
+pub mod future {
+    pub use super::atomic_waker::AtomicWaker;
+    pub use super::executor::{Executor, Task};
+    pub use shuttle::future::block_on;
+}
+
+pub mod hint {
+    pub use shuttle::hint::spin_loop;
+    pub use std::hint::unreachable_unchecked;
+}
+
+pub mod sync {
+    pub use super::once_lock::{LazyLock, OnceLock};
+    pub use shuttle::sync::{
+        Arc, Barrier, BarrierWaitResult, Condvar, Mutex, MutexGuard, Once, OnceState, RwLock,
+        RwLockReadGuard, RwLockWriteGuard, WaitTimeoutResult, Weak,
+    };
+    pub use std::sync::{LockResult, TryLockResult};
+
+    pub mod atomic {
+        pub use crate::imp::atomic::*;
+    }
+
+    pub mod mpsc {
+        pub use shuttle::sync::mpsc::{channel, Receiver, Sender};
+    }
+}
+
+pub mod thread {
+    pub use shuttle::thread::{
+        current, panicking, park, scope, spawn, yield_now, AccessError, Builder, JoinHandle,
+        LocalKey, Scope, ScopedJoinHandle, Thread, ThreadId,
+    };
+}
+
+#[path = "shuttle/lazy.rs"]
+mod lazy;
+
+pub use self::lazy::Static;
+pub use shuttle::{lazy_static, thread_local};
+
+#[path = "loom/once_lock.rs"]
+mod once_lock;
+
+#[path = "imp/atomic_waker.rs"]
+mod atomic_waker;
+
+#[path = "imp/executor.rs"]
+mod executor;
+
+// Atomics are wrapped rather than re-exported, to match loom's API.
+#[path = "imp/atomic.rs"]
+mod atomic;
+
+mod stress {
+    #[inline(always)]
+    pub(crate) fn yield_point() {}
+}
+
+use crate::model::{env_iterations, Builder};
+
+/// Iterations run by `loomy::model` when `LOOMY_ITERATIONS` is not set.
+const DEFAULT_ITERATIONS: usize = 1_000;
+
+/// Run the model closure under shuttle's random scheduler.
+///
+/// The closure is run `LOOMY_ITERATIONS` times, or 1000 times by default.
+pub fn model<F>(f: F)
+where
+    F: Fn() + Sync + Send + 'static,
+{
+    check(&new_builder(), f)
+}
+
+pub(crate) fn new_builder() -> Builder {
+    Builder {
+        iterations: env_iterations().unwrap_or(DEFAULT_ITERATIONS),
+        ..Builder::base()
+    }
+}
+
+pub(crate) fn check<F>(builder: &Builder, f: F)
+where
+    F: Fn() + Sync + Send + 'static,
+{
+    let iterations = builder.checked_iterations();
+    let f = move || crate::tracked::execution(&f);
+
+    let mut config = shuttle::Config::new();
+    config.max_time = builder.max_duration;
+
+    match builder.pct_depth {
+        Some(depth) => {
+            let scheduler = shuttle::scheduler::PctScheduler::new(depth, iterations);
+            shuttle::Runner::new(scheduler, config).run(f);
+        }
+        None => {
+            let scheduler = shuttle::scheduler::RandomScheduler::new(iterations);
+            shuttle::Runner::new(scheduler, config).run(f);
+        }
+    }
+}
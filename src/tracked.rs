@@ -0,0 +1,295 @@
+//! Drop accounting for values created within a model execution.
+//!
+//! Every execution gets a registry of the values created during it, which is
+//! checked for leaks once the model closure returns. Like observations, the
+//! registry lives outside of the model, so tracking is never an interleaving
+//! point.
+//!
+//! With the `checked` feature, allocations made through `loomy::alloc` under
+//! `std` and `shuttle` are accounted for in the same way, much like `loom`
+//! does for its own.
+
+#[cfg(all(feature = "checked", not(loom)))]
+use std::alloc::Layout;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::mem::ManuallyDrop;
+use std::ops::{Deref, DerefMut};
+use std::panic::Location;
+use std::ptr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::context;
+
+/// The creation sites of the values alive in an execution, by id.
+type Live = BTreeMap<u64, &'static Location<'static>>;
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+    executions: BTreeMap::new(),
+    #[cfg(all(feature = "checked", not(loom)))]
+    allocations: BTreeMap::new(),
+});
+
+struct Registry {
+    /// The values alive in each running execution.
+    executions: BTreeMap<u64, Live>,
+
+    /// Every live allocation by address, whether made within an execution or
+    /// not, so that any double deallocation is caught.
+    #[cfg(all(feature = "checked", not(loom)))]
+    allocations: BTreeMap<usize, Allocation>,
+}
+
+#[cfg(all(feature = "checked", not(loom)))]
+struct Allocation {
+    execution: Option<u64>,
+    layout: Layout,
+    site: &'static Location<'static>,
+}
+
+/// Hands out ids to executions and tracked values alike.
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A value whose drop is accounted for by the model.
+///
+/// Every `Tracked` value created within [`model`](crate::model) must be dropped
+/// exactly once before the model closure returns. Otherwise the execution
+/// fails, reporting where each leaked value was created, and a value dropped
+/// twice (for instance after being duplicated with `ptr::read`) panics.
+///
+/// Unlike `alloc::Track`, this is checked under every backend.
+///
+/// ```rust
+/// use loomy::sync::Mutex;
+/// use loomy::Tracked;
+///
+/// loomy::model(|| {
+///     let node = Mutex::new(Some(Tracked::new(1)));
+///
+///     assert_eq!(node.lock().unwrap().take().as_deref(), Some(&1));
+/// });
+/// ```
+///
+/// Values owned by threads which are still running when the model closure
+/// returns count as leaked, so join every thread first. Under `std`, values
+/// are only tracked on threads spawned through `loomy::thread`.
+pub struct Tracked<T> {
+    value: T,
+    key: Option<(u64, u64)>,
+    site: &'static Location<'static>,
+}
+
+impl<T> Tracked<T> {
+    /// Track `value`, recording the caller as its creation site.
+    #[track_caller]
+    pub fn new(value: T) -> Tracked<T> {
+        let site = Location::caller();
+
+        Tracked {
+            value,
+            key: register(site),
+            site,
+        }
+    }
+
+    /// Get a reference to the value.
+    pub fn get_ref(&self) -> &T {
+        &self.value
+    }
+
+    /// Get a mutable reference to the value.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+
+    /// Stop tracking the value, returning it.
+    pub fn into_inner(self) -> T {
+        let this = ManuallyDrop::new(self);
+        release(this.key, this.site);
+
+        // SAFETY: `this` is never used again, nor dropped.
+        unsafe { ptr::read(&this.value) }
+    }
+}
+
+impl<T> Drop for Tracked<T> {
+    fn drop(&mut self) {
+        release(self.key, self.site);
+    }
+}
+
+impl<T> Deref for Tracked<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for Tracked<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Tracked<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Tracked").field(&self.value).finish()
+    }
+}
+
+/// Run `f` as a model execution, failing if any value tracked meanwhile is
+/// still alive once it returns.
+pub(crate) fn execution(f: impl FnOnce()) {
+    struct Reset {
+        id: u64,
+        previous: Option<u64>,
+    }
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            context::update(|context| context.execution = self.previous);
+            finish(self.id);
+        }
+    }
+
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+
+    lock().executions.insert(id, Live::new());
+
+    // Stop tracking even if the execution fails.
+    let _reset = Reset {
+        id,
+        previous: context::update(|context| context.execution.replace(id)),
+    };
+
+    f();
+
+    let leaked = finish(id);
+
+    if !leaked.is_empty() {
+        panic!("{}", leaked.join("\n"));
+    }
+}
+
+/// Stop tracking execution `id`, describing everything it leaked.
+fn finish(id: u64) -> Vec<String> {
+    let mut registry = lock();
+    let mut leaked = Vec::new();
+
+    if let Some(live) = registry.executions.remove(&id).filter(|l| !l.is_empty()) {
+        leaked.push(format!(
+            "loomy: {} tracked value(s) leaked, created at:",
+            live.len()
+        ));
+
+        leaked.extend(live.values().map(|site| format!("  {site}")));
+    }
+
+    #[cfg(all(feature = "checked", not(loom)))]
+    {
+        let mut allocations = registry
+            .allocations
+            .values_mut()
+            .filter(|a| a.execution == Some(id))
+            .collect::<Vec<_>>();
+
+        if !allocations.is_empty() {
+            leaked.push(format!(
+                "loomy: {} allocation(s) leaked, allocated at:",
+                allocations.len()
+            ));
+        }
+
+        allocations.sort_by_key(|a| (a.site.file(), a.site.line(), a.site.column()));
+
+        for allocation in allocations {
+            let (site, size) = (allocation.site, allocation.layout.size());
+            leaked.push(format!("  {site} ({size} bytes)"));
+
+            // Leaks are reported once, but may still be deallocated later.
+            allocation.execution = None;
+        }
+    }
+
+    leaked
+}
+
+/// Register a value created at `site` with the current execution, if any.
+fn register(site: &'static Location<'static>) -> Option<(u64, u64)> {
+    let execution = context::with(|context| context.execution)?;
+    let mut registry = lock();
+
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    registry.executions.get_mut(&execution)?.insert(id, site);
+
+    Some((execution, id))
+}
+
+/// Unregister a value created at `site`.
+fn release(key: Option<(u64, u64)>, site: &'static Location<'static>) {
+    let Some((execution, id)) = key else {
+        return;
+    };
+
+    let mut registry = lock();
+
+    // Values outliving their execution were already reported as leaked.
+    let Some(live) = registry.executions.get_mut(&execution) else {
+        return;
+    };
+
+    if live.remove(&id).is_none() {
+        drop(registry);
+        panic!("loomy: tracked value created at {site} dropped twice");
+    }
+}
+
+/// Register an allocation of `layout` at `ptr`, made at `site`.
+#[cfg(all(feature = "checked", not(loom)))]
+pub(crate) fn register_alloc(ptr: *mut u8, layout: Layout, site: &'static Location<'static>) {
+    if ptr.is_null() {
+        return;
+    }
+
+    let allocation = Allocation {
+        execution: context::with(|context| context.execution),
+        layout,
+        site,
+    };
+
+    lock().allocations.insert(ptr as usize, allocation);
+}
+
+/// Unregister the allocation at `ptr`, which `site` is about to deallocate
+/// with `layout`.
+///
+/// # Panics
+///
+/// Panics if `ptr` is not allocated, or was allocated with another layout.
+#[cfg(all(feature = "checked", not(loom)))]
+pub(crate) fn release_alloc(ptr: *mut u8, layout: Layout, site: &'static Location<'static>) {
+    let mut registry = lock();
+
+    let message = match registry.allocations.get(&(ptr as usize)) {
+        None => format!("loomy: deallocation at {site} of {ptr:?}, which is not allocated"),
+        Some(allocation) if allocation.layout != layout => format!(
+            "loomy: deallocation at {site} with {layout:?}, but {ptr:?} was allocated at {} with {:?}",
+            allocation.site, allocation.layout,
+        ),
+        Some(_) => {
+            registry.allocations.remove(&(ptr as usize));
+            return;
+        }
+    };
+
+    drop(registry);
+    panic!("{message}");
+}
+
+fn lock() -> MutexGuard<'static, Registry> {
+    // Registrations are whole inserts and removals, so a panic cannot leave
+    // them torn.
+    REGISTRY.lock().unwrap_or_else(PoisonError::into_inner)
+}
@@ -0,0 +1,123 @@
+//! `sync::Arc` and `sync::Weak` under `loom`, which loomy implements itself.
+
+#![cfg(loom)]
+
+use std::panic::{self, AssertUnwindSafe};
+
+use loomy::sync::atomic::{AtomicUsize, Ordering};
+use loomy::sync::{Arc, Weak};
+use loomy::thread;
+
+#[test]
+fn upgrade_races_the_last_drop() {
+    loomy::model(|| {
+        let arc = Arc::new(AtomicUsize::new(1));
+        let weak = Arc::downgrade(&arc);
+
+        let t = thread::spawn(move || weak.upgrade().map(|n| n.load(Ordering::Relaxed)));
+
+        drop(arc);
+
+        assert!(matches!(t.join().unwrap(), Some(1) | None));
+    });
+}
+
+#[test]
+fn downgrade_races_get_mut() {
+    loomy::model(|| {
+        let mut arc = Arc::new(1);
+        let arc2 = Arc::clone(&arc);
+
+        let t = thread::spawn(move || {
+            let weak = Arc::downgrade(&arc2);
+            drop(arc2);
+            weak
+        });
+
+        // The other thread holds either the `Arc` or the `Weak` throughout.
+        assert!(Arc::get_mut(&mut arc).is_none());
+
+        let weak = t.join().unwrap();
+        assert!(Arc::get_mut(&mut arc).is_none());
+
+        drop(weak);
+        assert_eq!(Arc::get_mut(&mut arc), Some(&mut 1));
+    });
+}
+
+#[test]
+fn make_mut_with_only_weaks_remaining() {
+    loomy::model(|| {
+        let mut arc = Arc::new(1);
+        let weak = Arc::downgrade(&arc);
+
+        let t = thread::spawn(move || weak.upgrade().map(|n| *n));
+
+        *Arc::make_mut(&mut arc) = 2;
+
+        assert!(matches!(t.join().unwrap(), Some(1) | None));
+        assert_eq!(*arc, 2);
+        assert_eq!(Arc::weak_count(&arc), 0);
+    });
+}
+
+#[test]
+fn into_inner_races_on_two_threads() {
+    loomy::model(|| {
+        let arc = Arc::new(1);
+        let arc2 = Arc::clone(&arc);
+
+        let t = thread::spawn(move || Arc::into_inner(arc2));
+        let mine = Arc::into_inner(arc);
+        let theirs = t.join().unwrap();
+
+        assert!(matches!((mine, theirs), (Some(1), None) | (None, Some(1))));
+    });
+}
+
+#[test]
+fn new_cyclic_frees_the_allocation_when_the_data_fn_panics() {
+    loomy::model(|| {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            Arc::<u8>::new_cyclic(|weak: &Weak<u8>| {
+                assert!(weak.upgrade().is_none());
+                panic!("data fn panicked");
+            })
+        }));
+
+        assert!(result.is_err());
+    });
+}
+
+#[test]
+fn into_raw_round_trips() {
+    loomy::model(|| {
+        let arc = Arc::new(1);
+        let ptr = Arc::into_raw(Arc::clone(&arc));
+
+        // SAFETY: `ptr` comes from `into_raw` and is converted back once.
+        let back = unsafe { Arc::from_raw(ptr) };
+        assert!(Arc::ptr_eq(&arc, &back));
+        assert_eq!(Arc::strong_count(&arc), 2);
+
+        let slice: Arc<[String]> = Arc::from(vec!["a".to_string(), "b".to_string()]);
+        let ptr = Arc::into_raw(slice);
+
+        // SAFETY: As above.
+        let slice = unsafe { Arc::from_raw(ptr) };
+        assert_eq!(*slice, ["a", "b"]);
+    });
+}
+
+#[test]
+fn unsized_values_are_moved_in() {
+    loomy::model(|| {
+        let text: Arc<str> = Arc::from_std(std::sync::Arc::from("text"));
+        let weak = Arc::downgrade(&text);
+
+        let t = thread::spawn(move || weak.upgrade().map(|s| s.len()));
+        drop(text);
+
+        assert!(matches!(t.join().unwrap(), Some(4) | None));
+    });
+}
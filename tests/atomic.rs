@@ -0,0 +1,45 @@
+//! Invalid orderings, which loomy's atomics blame on the caller.
+
+#![cfg(not(loom))]
+
+mod common;
+
+use common::{is_child, run_child};
+use loomy::sync::atomic::{AtomicUsize, Ordering};
+
+#[test]
+fn release_load() {
+    if is_child() {
+        AtomicUsize::new(0).load(Ordering::Release);
+    }
+}
+
+#[test]
+fn acquire_store() {
+    if is_child() {
+        AtomicUsize::new(0).store(1, Ordering::Acquire);
+    }
+}
+
+/// Run `test`, checking that it panics with `message` at a line of this file.
+fn assert_blames_the_caller(test: &str, message: &str) {
+    let output = run_child(test, &[]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(!output.status.success());
+    assert!(stderr.contains("panicked at tests/atomic.rs:"), "{stderr}");
+    assert!(stderr.contains(message), "{stderr}");
+}
+
+#[test]
+fn release_loads_panic() {
+    assert_blames_the_caller("release_load", "there is no such thing as a release load");
+}
+
+#[test]
+fn acquire_stores_panic() {
+    assert_blames_the_caller(
+        "acquire_store",
+        "there is no such thing as an acquire store",
+    );
+}
@@ -0,0 +1,62 @@
+//! `sync::Barrier` under `loom`, which loomy implements itself.
+
+#![cfg(loom)]
+
+use loomy::sync::atomic::{AtomicUsize, Ordering};
+use loomy::sync::{Arc, Barrier};
+use loomy::thread;
+
+#[test]
+fn one_leader_per_generation() {
+    loomy::model(|| {
+        let barrier = Arc::new(Barrier::new(2));
+        let arrived = Arc::new(AtomicUsize::new(0));
+
+        let wait = {
+            let (barrier, arrived) = (Arc::clone(&barrier), Arc::clone(&arrived));
+
+            move || {
+                arrived.fetch_add(1, Ordering::Relaxed);
+                let leader = barrier.wait().is_leader();
+
+                // Nobody leaves before everybody has arrived.
+                assert_eq!(arrived.load(Ordering::Relaxed), 2);
+                leader
+            }
+        };
+
+        let t = thread::spawn(wait.clone());
+        let mine = wait();
+
+        assert_ne!(mine, t.join().unwrap());
+    });
+}
+
+#[test]
+fn barriers_are_reusable() {
+    loomy::model(|| {
+        let barrier = Arc::new(Barrier::new(2));
+        let arrived = Arc::new([AtomicUsize::new(0), AtomicUsize::new(0)]);
+
+        let wait = {
+            let (barrier, arrived) = (Arc::clone(&barrier), Arc::clone(&arrived));
+
+            move || {
+                let mut leaders = [false; 2];
+
+                for (generation, leader) in leaders.iter_mut().enumerate() {
+                    arrived[generation].fetch_add(1, Ordering::Relaxed);
+                    *leader = barrier.wait().is_leader();
+                    assert_eq!(arrived[generation].load(Ordering::Relaxed), 2);
+                }
+
+                leaders
+            }
+        };
+
+        let t = thread::spawn(wait.clone());
+        let mine = wait();
+
+        assert_eq!(mine.map(|leader| !leader), t.join().unwrap());
+    });
+}
@@ -0,0 +1,47 @@
+//! Overlapping `UnsafeCell` accesses under `std` with the `checked` feature.
+
+#![cfg(all(feature = "checked", not(any(loom, feature = "shuttle"))))]
+
+use std::panic::{self, AssertUnwindSafe};
+
+use loomy::cell::UnsafeCell;
+
+#[test]
+fn disjoint_accesses_are_allowed() {
+    let cell = UnsafeCell::new(0);
+
+    // SAFETY: The accesses do not overlap.
+    cell.with_mut(|n| unsafe { *n += 1 });
+    cell.with(|a| cell.with(|b| assert_eq!(unsafe { (*a, *b) }, (1, 1))));
+}
+
+#[test]
+#[should_panic(expected = "mutable access to UnsafeCell at")]
+fn with_mut_overlapping_with_panics() {
+    let cell = UnsafeCell::new(0);
+
+    cell.with(|_| cell.with_mut(|_| ()));
+}
+
+#[test]
+#[should_panic(expected = "immutable access to UnsafeCell at")]
+fn with_overlapping_with_mut_panics() {
+    let cell = UnsafeCell::new(0);
+
+    cell.with_mut(|_| cell.with(|_| ()));
+}
+
+#[test]
+fn overlaps_name_both_accesses() {
+    let cell = UnsafeCell::new(0);
+
+    let payload =
+        panic::catch_unwind(AssertUnwindSafe(|| cell.with(|_| cell.get_mut()))).unwrap_err();
+    let message = payload.downcast_ref::<String>().unwrap();
+
+    assert_eq!(
+        message.matches(" at tests/cell.rs:").count(),
+        2,
+        "{message}"
+    );
+}
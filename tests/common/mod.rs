@@ -0,0 +1,27 @@
+//! Helpers for tests which must run in a process of their own.
+
+use std::env;
+use std::process::{Command, Output};
+
+/// Whether this process is a child spawned by [`run_child`].
+pub fn is_child() -> bool {
+    env::var_os("LOOMY_TEST_CHILD").is_some()
+}
+
+/// Run `test` from the current test binary in a child process with `vars` set,
+/// returning its output.
+pub fn run_child(test: &str, vars: &[(&str, &str)]) -> Output {
+    let exe = env::current_exe().unwrap();
+
+    let mut command = Command::new(exe);
+
+    command
+        .args([test, "--exact", "--nocapture", "--test-threads=1"])
+        .env("LOOMY_TEST_CHILD", "1")
+        .env_remove("LOOMY_ITERATIONS")
+        .env_remove("LOOMY_SEED")
+        .env_remove("LOOMY_TIMEOUT")
+        .envs(vars.iter().copied());
+
+    command.output().unwrap()
+}
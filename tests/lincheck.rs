@@ -0,0 +1,62 @@
+//! Linearizability checking of a counter, correct and otherwise.
+
+use loomy::lincheck::{self, Spec};
+use loomy::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Debug)]
+struct Increment;
+
+#[derive(Clone, Default)]
+struct Counter(usize);
+
+impl Spec for Counter {
+    type Op = Increment;
+    type Ret = usize;
+
+    fn apply(&mut self, _: &Increment) -> usize {
+        self.0 += 1;
+        self.0 - 1
+    }
+}
+
+fn threads() -> Vec<Vec<Increment>> {
+    vec![vec![Increment], vec![Increment]]
+}
+
+#[test]
+fn linearizable_histories_pass() {
+    lincheck::check(
+        Counter::default(),
+        threads(),
+        || AtomicUsize::new(0),
+        |n, _| n.fetch_add(1, Ordering::SeqCst),
+    );
+}
+
+#[test]
+#[should_panic(expected = "history is not linearizable")]
+fn non_linearizable_histories_panic() {
+    // Never storing the increment, both calls return zero in every execution.
+    lincheck::check(
+        Counter::default(),
+        threads(),
+        || AtomicUsize::new(0),
+        |n, _| n.load(Ordering::SeqCst),
+    );
+}
+
+#[cfg(loom)]
+#[test]
+#[should_panic(expected = "history is not linearizable")]
+fn lost_updates_are_found() {
+    lincheck::check(
+        Counter::default(),
+        threads(),
+        || AtomicUsize::new(0),
+        |n, _| {
+            let value = n.load(Ordering::SeqCst);
+            n.store(value + 1, Ordering::SeqCst);
+            value
+        },
+    );
+}
@@ -0,0 +1,54 @@
+//! Checking outcomes of litmus tests.
+
+#[test]
+#[should_panic(expected = "loomy: forbidden outcome `r == 0` observed with r = 0")]
+fn forbidden_outcomes_panic() {
+    loomy::litmus! {
+        shared x;
+        thread { let r = x.load(Relaxed); }
+        forbidden r == 0;
+    };
+}
+
+#[test]
+fn reached_outcomes_are_reported() {
+    let report = loomy::litmus! {
+        shared x;
+
+        thread {
+            x.store(1, Relaxed);
+            let r = x.load(Relaxed);
+        }
+
+        allowed r == 0;
+        allowed r == 1;
+    };
+
+    assert_eq!(
+        report.allowed().collect::<Vec<_>>(),
+        [("r == 0", false), ("r == 1", true)]
+    );
+}
+
+/// `loom` treats `SeqCst` as `AcqRel`, so store buffering is observed even
+/// though every access is `SeqCst`.
+#[cfg(loom)]
+#[test]
+#[should_panic(expected = "loomy: forbidden outcome `r1 == 0 && r2 == 0` observed")]
+fn loom_observes_seq_cst_store_buffering() {
+    loomy::litmus! {
+        shared x, y;
+
+        thread {
+            x.store(1, SeqCst);
+            let r1 = y.load(SeqCst);
+        }
+
+        thread {
+            y.store(1, SeqCst);
+            let r2 = x.load(SeqCst);
+        }
+
+        forbidden r1 == 0 && r2 == 0;
+    };
+}
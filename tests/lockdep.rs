@@ -0,0 +1,61 @@
+//! Lock-order checking under `std` with the `checked` feature.
+
+#![cfg(all(feature = "checked", not(any(loom, feature = "shuttle"))))]
+
+use std::panic;
+
+use loomy::sync::{Mutex, RwLock};
+use loomy::thread;
+
+fn lock_in_order(first: &Mutex<()>, second: &Mutex<()>) {
+    let _first = first.lock().unwrap();
+    let _second = second.lock().unwrap();
+}
+
+#[test]
+fn consistent_orders_are_allowed() {
+    let (a, b) = (Mutex::new(()), Mutex::new(()));
+
+    lock_in_order(&a, &b);
+    lock_in_order(&a, &b);
+}
+
+#[test]
+#[should_panic(expected = "lock order inversion")]
+fn inversions_panic() {
+    let (a, b) = (Mutex::new(()), Mutex::new(()));
+
+    lock_in_order(&a, &b);
+    lock_in_order(&b, &a);
+}
+
+#[test]
+#[should_panic(expected = "lock order inversion")]
+fn inversions_across_readers_and_writers_panic() {
+    let (a, b) = (RwLock::new(()), RwLock::new(()));
+
+    {
+        let _a = a.read().unwrap();
+        let _b = b.write().unwrap();
+    }
+
+    let _b = b.read().unwrap();
+    let _a = a.write().unwrap();
+}
+
+#[test]
+fn inversions_across_threads_report_both_backtraces() {
+    let (a, b) = (Mutex::new(()), Mutex::new(()));
+
+    thread::scope(|s| {
+        s.spawn(|| lock_in_order(&a, &b));
+    });
+
+    let payload = panic::catch_unwind(|| lock_in_order(&b, &a)).unwrap_err();
+    let report = payload.downcast_ref::<String>().unwrap();
+
+    assert!(report.contains("but a lock was previously acquired at"));
+
+    // Both backtraces are captured regardless of `RUST_BACKTRACE`.
+    assert!(report.matches("lock_in_order").count() >= 2, "{report}");
+}
@@ -0,0 +1,64 @@
+//! Arguments of `#[loomy::test]` with `std`.
+
+#![cfg(not(any(loom, feature = "shuttle")))]
+
+mod common;
+
+use std::time::Duration;
+
+use common::{is_child, run_child};
+
+#[loomy::test(ignore_in_std)]
+fn ignored_in_std() {
+    panic!("ran under `std`");
+}
+
+#[test]
+fn ignore_in_std_ignores_the_test() {
+    let output = run_child("ignored_in_std", &[]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success(), "{stdout}");
+    assert!(
+        stdout.contains("test ignored_in_std ... ignored"),
+        "{stdout}"
+    );
+}
+
+#[loomy::test(std_iterations = 7)]
+fn seven_iterations() {
+    println!("iteration");
+}
+
+#[test]
+fn std_iterations_reach_the_builder() {
+    let output = run_child("seven_iterations", &[]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success(), "{stdout}");
+    assert_eq!(stdout.matches("iteration\n").count(), 7, "{stdout}");
+}
+
+#[loomy::test(std_timeout = 1)]
+fn times_out() {
+    if is_child() {
+        std::thread::sleep(Duration::from_secs(5));
+    }
+}
+
+#[test]
+fn std_timeout_reaches_the_builder() {
+    let output = run_child("times_out", &[]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(!output.status.success());
+    assert!(
+        stderr.contains("loomy: execution still running after 1s"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn unknown_arguments_fail_to_compile() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
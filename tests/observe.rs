@@ -0,0 +1,52 @@
+//! Outcome collection under `std`, where threads only record into a session
+//! when spawned through loomy.
+
+#![cfg(not(any(loom, feature = "shuttle")))]
+
+use std::collections::BTreeSet;
+use std::sync::Barrier;
+
+use loomy::thread;
+
+#[test]
+fn observations_from_other_threads_are_ignored() {
+    let outcomes = loomy::outcomes(|| {
+        std::thread::spawn(|| loomy::observe("other", 1))
+            .join()
+            .unwrap();
+
+        thread::spawn(|| loomy::observe("spawned", 2))
+            .join()
+            .unwrap();
+
+        thread::scope(|s| {
+            s.spawn(|| loomy::observe("scoped", 3));
+        });
+    });
+
+    assert_eq!(
+        outcomes.labels().collect::<BTreeSet<_>>(),
+        BTreeSet::from(["scoped", "spawned"]),
+    );
+}
+
+#[test]
+fn concurrent_sessions_are_kept_apart() {
+    static BARRIER: Barrier = Barrier::new(2);
+
+    let session = |n: usize| {
+        move || {
+            loomy::outcomes(move || {
+                BARRIER.wait();
+                loomy::observe("n", n);
+                BARRIER.wait();
+            })
+        }
+    };
+
+    let a = std::thread::spawn(session(1));
+    let b = std::thread::spawn(session(2));
+
+    assert_eq!(a.join().unwrap().get::<usize>("n"), BTreeSet::from([1]));
+    assert_eq!(b.join().unwrap().get::<usize>("n"), BTreeSet::from([2]));
+}
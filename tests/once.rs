@@ -0,0 +1,110 @@
+//! `sync::Once`, `sync::OnceLock` and `sync::LazyLock` under `loom`, which
+//! loomy implements itself.
+
+#![cfg(loom)]
+
+use std::panic::{self, AssertUnwindSafe};
+
+use loomy::sync::atomic::{AtomicUsize, Ordering};
+use loomy::sync::{Arc, LazyLock, Once, OnceLock};
+use loomy::thread;
+
+#[test]
+fn get_or_init_races_set() {
+    loomy::model(|| {
+        let lock = Arc::new(OnceLock::new());
+        let lock2 = Arc::clone(&lock);
+
+        let t = thread::spawn(move || lock2.set(1));
+        let value = *lock.get_or_init(|| 2);
+
+        match t.join().unwrap() {
+            Ok(()) => assert_eq!(value, 1),
+            Err(rejected) => assert_eq!((value, rejected), (2, 1)),
+        }
+
+        assert_eq!(lock.get(), Some(&value));
+    });
+}
+
+#[test]
+fn get_or_init_runs_one_initializer() {
+    loomy::model(|| {
+        let lock = Arc::new(OnceLock::new());
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let init = {
+            let (lock, calls) = (Arc::clone(&lock), Arc::clone(&calls));
+
+            move |value: usize| {
+                *lock.get_or_init(|| {
+                    calls.fetch_add(1, Ordering::Relaxed);
+                    value
+                })
+            }
+        };
+
+        let init2 = init.clone();
+        let t = thread::spawn(move || init2(1));
+        let mine = init(2);
+
+        assert_eq!(t.join().unwrap(), mine);
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+    });
+}
+
+#[test]
+fn lazy_lock_is_forced_from_two_threads() {
+    loomy::model(|| {
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let lazy = Arc::new(LazyLock::new({
+            let calls = Arc::clone(&calls);
+            move || calls.fetch_add(1, Ordering::Relaxed) + 10
+        }));
+
+        let lazy2 = Arc::clone(&lazy);
+        let t = thread::spawn(move || *LazyLock::force(&lazy2));
+
+        assert_eq!(**lazy, 10);
+        assert_eq!(t.join().unwrap(), 10);
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+    });
+}
+
+#[test]
+fn call_once_force_recovers_a_poisoned_once() {
+    loomy::model(|| {
+        let once = Arc::new(Once::new());
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            once.call_once(|| panic!("initializer panicked"));
+        }));
+
+        assert!(result.is_err());
+        assert!(!once.is_completed());
+
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        let force = {
+            let (once, runs) = (Arc::clone(&once), Arc::clone(&runs));
+
+            move || {
+                once.call_once_force(|state| {
+                    assert!(state.is_poisoned());
+                    runs.fetch_add(1, Ordering::Relaxed);
+                })
+            }
+        };
+
+        let t = thread::spawn(force.clone());
+        force();
+        t.join().unwrap();
+
+        assert!(once.is_completed());
+        assert_eq!(runs.load(Ordering::Relaxed), 1);
+
+        // Completed, so neither runs nor panics.
+        once.call_once(|| unreachable!());
+    });
+}
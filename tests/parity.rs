@@ -0,0 +1,451 @@
+//! Compile-time checks that every item exported by loomy resolves with the same
+//! signature under each backend.
+//!
+//! Run under all of them:
+//!
+//! ```sh
+//! $ cargo test --test parity
+//! $ RUSTFLAGS="--cfg loom" cargo test --test parity
+//! $ cargo test --test parity --features shuttle
+//! ```
+
+// `thread_local!` only accepts `const` initializers under `std`.
+#![allow(
+    dead_code,
+    clippy::type_complexity,
+    clippy::missing_const_for_thread_local
+)]
+
+use std::time::Duration;
+
+use loomy::{alloc, cell, future, hint, sync, thread};
+
+fn alloc() {
+    use alloc::{Layout, Track};
+
+    let _: unsafe fn(Layout) -> *mut u8 = alloc::alloc;
+    let _: unsafe fn(Layout) -> *mut u8 = alloc::alloc_zeroed;
+    let _: unsafe fn(*mut u8, Layout) = alloc::dealloc;
+    let _: unsafe fn(*mut u8, Layout, usize) -> *mut u8 = alloc::realloc;
+
+    let _: fn(u8) -> Track<u8> = Track::new;
+    let _: fn(&Track<u8>) -> &u8 = Track::get_ref;
+    let _: fn(&mut Track<u8>) -> &mut u8 = Track::get_mut;
+    let _: fn(Track<u8>) -> u8 = Track::into_inner;
+}
+
+fn cell() {
+    use cell::{Cell, ConstPtr, MutPtr, UnsafeCell};
+
+    let _: fn(u8) -> Cell<u8> = Cell::new;
+    let _: fn(&Cell<u8>) -> u8 = Cell::get;
+    let _: fn(&Cell<u8>, u8) = Cell::set;
+    let _: fn(&Cell<u8>, u8) -> u8 = Cell::replace;
+    let _: fn(&Cell<u8>) -> u8 = Cell::take;
+    let _: fn(Cell<u8>) -> u8 = Cell::into_inner;
+
+    let _: fn(u8) -> UnsafeCell<u8> = UnsafeCell::new;
+    let _: fn(u8) -> UnsafeCell<u8> = UnsafeCell::from;
+    let _: fn() -> UnsafeCell<u8> = UnsafeCell::default;
+    let _: fn(UnsafeCell<u8>) -> u8 = UnsafeCell::into_inner;
+    let _: fn(&UnsafeCell<u8>) -> ConstPtr<u8> = UnsafeCell::get;
+    let _: fn(&UnsafeCell<u8>) -> MutPtr<u8> = UnsafeCell::get_mut;
+
+    let _ = |c: &UnsafeCell<u8>| -> u8 { c.with(|p: *const u8| unsafe { *p }) };
+    let _ = |c: &UnsafeCell<u8>| c.with_mut(|p: *mut u8| unsafe { *p = 1 });
+
+    let _: for<'a> unsafe fn(&'a ConstPtr<u8>) -> &'a u8 = ConstPtr::deref;
+    let _ = |p: &ConstPtr<u8>| -> u8 { p.with(|p: *const u8| unsafe { *p }) };
+
+    let _: for<'a> unsafe fn(&'a MutPtr<u8>) -> &'a mut u8 = MutPtr::deref;
+    let _ = |p: &MutPtr<u8>| p.with(|p: *mut u8| unsafe { *p = 1 });
+}
+
+fn future() {
+    use future::{AtomicWaker, Executor, Task};
+    use std::future::Ready;
+    use std::task::Waker;
+
+    let _: fn(Ready<u8>) -> u8 = future::block_on;
+
+    let _: fn() -> AtomicWaker = AtomicWaker::new;
+    let _: fn(&AtomicWaker, Waker) = AtomicWaker::register;
+    let _: fn(&AtomicWaker, &Waker) = AtomicWaker::register_by_ref;
+    let _: fn(&AtomicWaker) = AtomicWaker::wake;
+    let _: fn(&AtomicWaker) -> Option<Waker> = AtomicWaker::take_waker;
+
+    let _: fn() -> Executor = Executor::new;
+    let _: fn(&Executor, Ready<u8>) -> Task<u8> = Executor::spawn;
+    let _: fn(Executor) = Executor::join;
+    let _: fn(&Task<u8>) -> bool = Task::is_finished;
+    let _: fn(Task<u8>) -> u8 = future::block_on::<Task<u8>>;
+}
+
+fn hint() {
+    let _: fn() = hint::spin_loop;
+    let _: unsafe fn() -> ! = hint::unreachable_unchecked;
+}
+
+macro_rules! atomic_common {
+    ($name:ty, $t:ty) => {{
+        let _: fn($t) -> $name = <$name>::new;
+        let _: fn($t) -> $name = <$name>::from;
+        let _: fn() -> $name = <$name>::default;
+        let _: fn($name) -> $t = <$name>::into_inner;
+        let _: unsafe fn(&$name) -> $t = <$name>::unsync_load;
+
+        let _: fn(&$name, Ordering) -> $t = <$name>::load;
+        let _: fn(&$name, $t, Ordering) = <$name>::store;
+        let _: fn(&$name, $t, Ordering) -> $t = <$name>::swap;
+        let _: fn(&$name, $t, $t, Ordering, Ordering) -> Result<$t, $t> = <$name>::compare_exchange;
+        let _: fn(&$name, $t, $t, Ordering, Ordering) -> Result<$t, $t> =
+            <$name>::compare_exchange_weak;
+        let _ = |a: &$name| -> Result<$t, $t> {
+            a.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |v: $t| Some(v))
+        };
+    }};
+}
+
+macro_rules! atomic_with_mut {
+    ($name:ty, $t:ty) => {{
+        let _ = |a: &mut $name| -> $t { a.with_mut(|v: &mut $t| *v) };
+    }};
+}
+
+macro_rules! atomic_fetch {
+    ($name:ty, $t:ty, $($method:ident)*) => {{
+        $(let _: fn(&$name, $t, Ordering) -> $t = <$name>::$method;)*
+    }};
+}
+
+macro_rules! atomic_int {
+    ($($name:ty, $t:ty;)*) => {
+        $(
+            atomic_common!($name, $t);
+            atomic_with_mut!($name, $t);
+            atomic_fetch!(
+                $name,
+                $t,
+                fetch_add fetch_sub fetch_and fetch_nand fetch_or fetch_xor fetch_max fetch_min
+            );
+        )*
+    };
+}
+
+fn atomic() {
+    use sync::atomic::{
+        fence, AtomicBool, AtomicI16, AtomicI32, AtomicI64, AtomicI8, AtomicIsize, AtomicPtr,
+        AtomicU16, AtomicU32, AtomicU64, AtomicU8, AtomicUsize, Ordering,
+    };
+
+    let _: fn(Ordering) = fence;
+    let _ = [
+        Ordering::Relaxed,
+        Ordering::Release,
+        Ordering::Acquire,
+        Ordering::AcqRel,
+        Ordering::SeqCst,
+    ];
+
+    atomic_common!(AtomicBool, bool);
+    atomic_fetch!(AtomicBool, bool, fetch_and fetch_nand fetch_or fetch_xor);
+
+    atomic_common!(AtomicPtr<u8>, *mut u8);
+    atomic_with_mut!(AtomicPtr<u8>, *mut u8);
+
+    atomic_int! {
+        AtomicI8, i8;
+        AtomicI16, i16;
+        AtomicI32, i32;
+        AtomicI64, i64;
+        AtomicIsize, isize;
+        AtomicU8, u8;
+        AtomicU16, u16;
+        AtomicU32, u32;
+        AtomicU64, u64;
+        AtomicUsize, usize;
+    }
+}
+
+fn sync() {
+    use sync::{
+        Arc, Barrier, BarrierWaitResult, Condvar, LazyLock, LockResult, Mutex, MutexGuard, Once,
+        OnceLock, OnceState, RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockResult,
+        WaitTimeoutResult, Weak,
+    };
+
+    let _: fn(u8) -> Arc<u8> = Arc::new;
+    let _: fn(&Arc<u8>) -> Arc<u8> = Arc::clone;
+    let _: fn(Arc<u8>) -> Result<u8, Arc<u8>> = Arc::try_unwrap;
+    let _: fn(&Arc<u8>) -> usize = Arc::strong_count;
+    let _: fn(&mut Arc<u8>) -> Option<&mut u8> = Arc::get_mut;
+    let _: fn(&Arc<u8>, &Arc<u8>) -> bool = Arc::ptr_eq;
+    let _: fn(Arc<u8>) -> *const u8 = Arc::into_raw;
+    let _: fn(&Arc<u8>) -> *const u8 = Arc::as_ptr;
+    let _: unsafe fn(*const u8) -> Arc<u8> = Arc::from_raw;
+    let _: fn(Arc<u8>) -> Option<u8> = Arc::into_inner;
+    let _: fn(&mut Arc<u8>) -> &mut u8 = Arc::make_mut;
+    let _: fn(fn(&Weak<u8>) -> u8) -> Arc<u8> = Arc::new_cyclic;
+    let _: fn(&Arc<u8>) -> Weak<u8> = Arc::downgrade;
+    let _: fn(&Arc<u8>) -> usize = Arc::weak_count;
+
+    let _: fn(Box<str>) -> Arc<str> = Arc::from;
+    let _: fn(Vec<u8>) -> Arc<[u8]> = Arc::from;
+    let _: fn(&Arc<str>) -> Weak<str> = Arc::downgrade;
+    let _: fn(&mut Arc<[u8]>) -> Option<&mut [u8]> = Arc::get_mut;
+    let _: fn(Arc<[u8]>) -> *const [u8] = Arc::into_raw;
+    let _: unsafe fn(*const [u8]) -> Arc<[u8]> = Arc::from_raw;
+    let _: fn(&Weak<str>) -> Option<Arc<str>> = Weak::upgrade;
+
+    let _: fn() -> Weak<u8> = Weak::new;
+    let _: fn(&Weak<u8>) -> Option<Arc<u8>> = Weak::upgrade;
+    let _: fn(&Weak<u8>) -> Weak<u8> = Weak::clone;
+    let _: fn(&Weak<u8>) -> usize = Weak::strong_count;
+    let _: fn(&Weak<u8>) -> usize = Weak::weak_count;
+    let _: fn(&Weak<u8>, &Weak<u8>) -> bool = Weak::ptr_eq;
+
+    let _: fn(u8) -> Mutex<u8> = Mutex::new;
+    let _: fn(&Mutex<u8>) -> LockResult<MutexGuard<'_, u8>> = Mutex::lock;
+    let _: fn(&Mutex<u8>) -> TryLockResult<MutexGuard<'_, u8>> = Mutex::try_lock;
+    let _: fn(&mut Mutex<u8>) -> LockResult<&mut u8> = Mutex::get_mut;
+    let _: fn(Mutex<u8>) -> LockResult<u8> = Mutex::into_inner;
+
+    let _: fn(u8) -> RwLock<u8> = RwLock::new;
+    let _: fn(&RwLock<u8>) -> LockResult<RwLockReadGuard<'_, u8>> = RwLock::read;
+    let _: fn(&RwLock<u8>) -> TryLockResult<RwLockReadGuard<'_, u8>> = RwLock::try_read;
+    let _: fn(&RwLock<u8>) -> LockResult<RwLockWriteGuard<'_, u8>> = RwLock::write;
+    let _: fn(&RwLock<u8>) -> TryLockResult<RwLockWriteGuard<'_, u8>> = RwLock::try_write;
+    let _: fn(&mut RwLock<u8>) -> LockResult<&mut u8> = RwLock::get_mut;
+    let _: fn(RwLock<u8>) -> LockResult<u8> = RwLock::into_inner;
+
+    let _: fn() -> Condvar = Condvar::new;
+    let _: for<'a> fn(&Condvar, MutexGuard<'a, u8>) -> LockResult<MutexGuard<'a, u8>> =
+        Condvar::wait;
+    let _: for<'a> fn(
+        &Condvar,
+        MutexGuard<'a, u8>,
+        Duration,
+    ) -> LockResult<(MutexGuard<'a, u8>, WaitTimeoutResult)> = Condvar::wait_timeout;
+    let _: fn(&Condvar) = Condvar::notify_one;
+    let _: fn(&Condvar) = Condvar::notify_all;
+    let _: fn(&WaitTimeoutResult) -> bool = WaitTimeoutResult::timed_out;
+
+    let _: fn(usize) -> Barrier = Barrier::new;
+    let _: fn(&Barrier) -> BarrierWaitResult = Barrier::wait;
+    let _: fn(&BarrierWaitResult) -> bool = BarrierWaitResult::is_leader;
+
+    let _: fn() -> Once = Once::new;
+    let _: fn(&Once, fn()) = Once::call_once;
+    let _: fn(&Once, fn(&OnceState)) = Once::call_once_force;
+    let _: fn(&Once) -> bool = Once::is_completed;
+    let _: fn(&OnceState) -> bool = OnceState::is_poisoned;
+
+    let _: fn() -> OnceLock<u8> = OnceLock::new;
+    let _: fn(&OnceLock<u8>) -> Option<&u8> = OnceLock::get;
+    let _: fn(&mut OnceLock<u8>) -> Option<&mut u8> = OnceLock::get_mut;
+    let _: fn(&OnceLock<u8>, u8) -> Result<(), u8> = OnceLock::set;
+    let _: fn(&OnceLock<u8>, fn() -> u8) -> &u8 = OnceLock::get_or_init;
+    let _: fn(OnceLock<u8>) -> Option<u8> = OnceLock::into_inner;
+    let _: fn(&mut OnceLock<u8>) -> Option<u8> = OnceLock::take;
+
+    let _: fn(fn() -> u8) -> LazyLock<u8> = LazyLock::new;
+    let _: fn(&LazyLock<u8>) -> &u8 = LazyLock::force;
+    let _: fn(&LazyLock<u8>) -> &u8 = <LazyLock<u8> as std::ops::Deref>::deref;
+}
+
+fn mpsc() {
+    use std::sync::mpsc::{RecvError, SendError, TryRecvError};
+    use sync::mpsc::{channel, Receiver, Sender};
+
+    let _: fn() -> (Sender<u8>, Receiver<u8>) = channel;
+    let _: fn(&Sender<u8>, u8) -> Result<(), SendError<u8>> = Sender::send;
+    let _: fn(&Sender<u8>) -> Sender<u8> = Sender::clone;
+    let _: fn(&Receiver<u8>) -> Result<u8, RecvError> = Receiver::recv;
+    let _: fn(&Receiver<u8>) -> Result<u8, TryRecvError> = Receiver::try_recv;
+}
+
+fn thread() {
+    use thread::{
+        AccessError, Builder, JoinHandle, LocalKey, Scope, ScopedJoinHandle, Thread, ThreadId,
+    };
+
+    let _: fn(fn() -> u8) -> JoinHandle<u8> = thread::spawn;
+    let _: fn() -> Thread = thread::current;
+    let _: fn() = thread::park;
+    let _: fn() = thread::yield_now;
+    let _: fn() -> bool = thread::panicking;
+
+    let _: fn(JoinHandle<u8>) -> std::thread::Result<u8> = JoinHandle::join;
+    let _: fn(&JoinHandle<u8>) -> &Thread = JoinHandle::thread;
+
+    let _: fn(&Thread) -> ThreadId = Thread::id;
+    let _: fn(&Thread) -> Option<&str> = Thread::name;
+    let _: fn(&Thread) = Thread::unpark;
+
+    let _: fn() -> Builder = Builder::new;
+    let _: fn(Builder, String) -> Builder = Builder::name;
+    let _: fn(Builder, usize) -> Builder = Builder::stack_size;
+    let _: fn(Builder, fn() -> u8) -> std::io::Result<JoinHandle<u8>> = Builder::spawn;
+
+    let _ = || -> std::thread::Result<u8> {
+        thread::scope(|s: &Scope<'_, '_>| {
+            let handle: ScopedJoinHandle<'_, u8> = s.spawn(|| 1);
+            let _: &Thread = handle.thread();
+            let _: bool = handle.is_finished();
+            handle.join()
+        })
+    };
+    let _: fn(fn() -> u8, fn() -> u16) -> (u8, u16) = loomy::join;
+
+    let _ = |key: &'static LocalKey<u8>| -> u8 { key.with(|v: &u8| *v) };
+    let _ = |key: &'static LocalKey<u8>| -> Result<u8, AccessError> { key.try_with(|v: &u8| *v) };
+}
+
+fn statics() {
+    use loomy::Static;
+    use sync::atomic::AtomicUsize;
+
+    static COUNTER: Static<AtomicUsize> = Static::new(|| AtomicUsize::new(0));
+
+    let _: &AtomicUsize = &COUNTER;
+
+    fn new<T: Sync>(init: fn() -> T) -> Static<T> {
+        Static::new(init)
+    }
+
+    fn get<T: Sync + 'static>(s: &'static Static<T>) -> &'static T {
+        s
+    }
+
+    let _: fn(fn() -> u8) -> Static<u8> = new;
+    let _: fn(&'static Static<u8>) -> &'static u8 = get;
+}
+
+fn macros() {
+    use thread::LocalKey;
+
+    loomy::thread_local! {
+        static LOCAL: u8 = 0;
+    }
+
+    loomy::lazy_static! {
+        static ref LAZY: u8 = 0;
+        pub(crate) static ref PUBLIC: u8 = 0;
+    }
+
+    let _: &'static LocalKey<u8> = &LOCAL;
+    let _: u8 = *LAZY;
+    let _: &u8 = &PUBLIC;
+}
+
+fn model() {
+    let _: fn(fn()) = loomy::model;
+
+    let mut builder = loomy::model::Builder::new();
+    builder.max_threads = 2;
+    builder.max_branches = 100;
+    builder.max_permutations = Some(1);
+    builder.max_duration = Some(Duration::from_secs(1));
+    builder.preemption_bound = Some(1);
+    builder.checkpoint_interval = 1;
+    builder.location = false;
+    builder.log = false;
+    builder.iterations = 1;
+    builder.pct_depth = None;
+    builder.timeout = Some(Duration::from_secs(1));
+
+    let _: &mut loomy::model::Builder = builder.checkpoint_file("checkpoint.json");
+    let _: fn(&loomy::model::Builder, fn()) = loomy::model::Builder::check;
+    let _: fn(&loomy::model::Builder, fn()) -> loomy::Outcomes = loomy::model::Builder::outcomes;
+}
+
+fn lincheck() {
+    use loomy::lincheck::{self, Spec};
+    use loomy::model::Builder;
+
+    #[derive(Clone)]
+    struct Register(u8);
+
+    impl Spec for Register {
+        type Op = u8;
+        type Ret = u8;
+
+        fn apply(&mut self, op: &u8) -> u8 {
+            std::mem::replace(&mut self.0, *op)
+        }
+    }
+
+    let _: fn(Register, Vec<Vec<u8>>, fn() -> u8, fn(&u8, &u8) -> u8) = lincheck::check;
+    let _: fn(&Builder, Register, Vec<Vec<u8>>, fn() -> u8, fn(&u8, &u8) -> u8) =
+        lincheck::check_with;
+}
+
+#[cfg(feature = "proptest")]
+fn proptest() {
+    use ::proptest::strategy::Just;
+    use ::proptest::test_runner::Config;
+    use loomy::model::Builder;
+    use loomy::proptest::{self, Scenario};
+
+    let _: fn(Vec<Vec<u8>>) -> Scenario<u8> = Scenario::new;
+    let _: fn(&Scenario<u8>) -> &[Vec<u8>] = Scenario::threads;
+    let _ = |s: &Scenario<u8>| s.run(|_: usize, _: &u8| {});
+
+    let _ = proptest::scenario(0..10u8, 1..3, 0..3);
+    let _: fn(Just<Scenario<u8>>, fn(&Scenario<u8>)) = proptest::check;
+    let _: fn(&Builder, Config, Just<Scenario<u8>>, fn(&Scenario<u8>)) = proptest::check_with;
+}
+
+fn litmus() {
+    use loomy::litmus::{Outcome, Report};
+
+    let _: Report = loomy::litmus! {
+        shared x = 0;
+        thread { let r = x.load(Relaxed); }
+        forbidden r == 1;
+        allowed r == 0;
+    };
+
+    let _: fn(&Outcome, &str) -> usize = Outcome::get;
+    let _ = |o: &Outcome| -> Vec<(&'static str, usize)> { o.registers().collect() };
+    let _ = |r: &Report| -> Option<Outcome> { r.outcomes().next().cloned() };
+    let _ = |r: &Report| -> Vec<(&'static str, bool)> { r.allowed().collect() };
+}
+
+fn tracked() {
+    use loomy::Tracked;
+    use std::ops::{Deref, DerefMut};
+
+    let _: fn(u8) -> Tracked<u8> = Tracked::new;
+    let _: fn(&Tracked<u8>) -> &u8 = Tracked::get_ref;
+    let _: fn(&mut Tracked<u8>) -> &mut u8 = Tracked::get_mut;
+    let _: fn(Tracked<u8>) -> u8 = Tracked::into_inner;
+    let _: fn(&Tracked<u8>) -> &u8 = Deref::deref;
+    let _: fn(&mut Tracked<u8>) -> &mut u8 = DerefMut::deref_mut;
+}
+
+fn observe() {
+    use loomy::Outcomes;
+    use std::collections::BTreeSet;
+
+    let _: fn(&'static str, u8) = loomy::observe;
+    let _: fn(fn()) -> Outcomes = loomy::outcomes;
+    let _: fn(&Outcomes, &str) -> BTreeSet<u8> = Outcomes::get;
+}
+
+#[test]
+fn parity() {
+    loomy::model(|| {
+        use sync::atomic::{AtomicUsize, Ordering};
+        use sync::Arc;
+
+        let n = Arc::new(AtomicUsize::new(0));
+        let n2 = Arc::clone(&n);
+
+        thread::spawn(move || n2.fetch_add(1, Ordering::Relaxed))
+            .join()
+            .unwrap();
+
+        assert_eq!(n.load(Ordering::Relaxed), 1);
+    });
+}
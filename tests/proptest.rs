@@ -0,0 +1,35 @@
+//! Shrinking of failing scenarios.
+
+#![cfg(feature = "proptest")]
+
+use std::panic;
+
+use ::proptest::strategy::Just;
+use loomy::proptest::{self, scenario};
+use loomy::sync::atomic::{AtomicUsize, Ordering};
+
+#[test]
+fn failing_scenarios_are_shrunk() {
+    let payload = panic::catch_unwind(|| {
+        proptest::check(scenario(Just("op"), 1..3, 0..3), |scenario| {
+            let ran = AtomicUsize::new(0);
+
+            scenario.run(|_, _| {
+                ran.fetch_add(1, Ordering::Relaxed);
+            });
+
+            // Fails with at least two threads and one operation.
+            assert!(scenario.threads().len() < 2 || ran.load(Ordering::Relaxed) == 0);
+        });
+    })
+    .unwrap_err();
+
+    let message = payload.downcast_ref::<String>().unwrap();
+
+    let (_, minimal) = message
+        .split_once("minimal failing scenario: ")
+        .unwrap_or_else(|| panic!("{message}"));
+
+    assert_eq!(minimal.matches("thread ").count(), 2, "{message}");
+    assert_eq!(minimal.matches("\"op\"").count(), 1, "{message}");
+}
@@ -0,0 +1,75 @@
+//! `thread::scope` under `loom`, which loomy implements itself.
+
+#![cfg(loom)]
+
+use std::panic::{self, AssertUnwindSafe};
+
+use loomy::sync::atomic::{AtomicUsize, Ordering};
+use loomy::thread;
+
+#[test]
+fn threads_borrowing_the_stack_are_joined() {
+    loomy::model(|| {
+        let n = AtomicUsize::new(0);
+
+        thread::scope(|s| {
+            s.spawn(|| n.store(1, Ordering::Relaxed));
+        });
+
+        assert_eq!(n.load(Ordering::Relaxed), 1);
+    });
+}
+
+#[test]
+fn threads_are_joined_when_the_body_panics() {
+    loomy::model(|| {
+        let n = AtomicUsize::new(0);
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            thread::scope(|s| {
+                s.spawn(|| n.store(1, Ordering::Relaxed));
+                panic!("scope body panicked");
+            })
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(n.load(Ordering::Relaxed), 1);
+    });
+}
+
+#[test]
+fn scoped_threads_spawn_into_the_scope() {
+    loomy::model(|| {
+        let n = AtomicUsize::new(0);
+
+        thread::scope(|s| {
+            s.spawn(|| {
+                s.spawn(|| n.fetch_add(1, Ordering::Relaxed));
+                n.fetch_add(1, Ordering::Relaxed);
+            });
+        });
+
+        assert_eq!(n.load(Ordering::Relaxed), 2);
+    });
+}
+
+#[test]
+fn join_after_the_scope_took_the_handle() {
+    loomy::model(|| {
+        let n = &AtomicUsize::new(0);
+
+        thread::scope(|s| {
+            let first = s.spawn(move || {
+                n.store(1, Ordering::Relaxed);
+                2
+            });
+
+            // The body returns at once, so the scope may join `first` before
+            // this thread does.
+            s.spawn(move || {
+                assert_eq!(first.join().unwrap(), 2);
+                assert_eq!(n.load(Ordering::Relaxed), 1);
+            });
+        });
+    });
+}
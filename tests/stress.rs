@@ -0,0 +1,62 @@
+//! Stress runs with `std`.
+
+#![cfg(not(any(loom, feature = "shuttle")))]
+
+mod common;
+
+use common::{is_child, run_child};
+
+#[test]
+fn failing_model() {
+    if is_child() {
+        loomy::model(|| panic!("failing on purpose"));
+    }
+}
+
+#[test]
+fn seed_is_replayed() {
+    let output = run_child("failing_model", &[("LOOMY_ITERATIONS", "3")]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(!output.status.success());
+    assert!(
+        stderr.contains("loomy: iteration 1 of 3 failed"),
+        "{stderr}"
+    );
+
+    let seed = stderr
+        .split("LOOMY_SEED=")
+        .nth(1)
+        .and_then(|rest| rest.split_whitespace().next())
+        .unwrap();
+
+    let output = run_child(
+        "failing_model",
+        &[("LOOMY_SEED", seed), ("LOOMY_ITERATIONS", "1")],
+    );
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    let replayed = format!("loomy: iteration 1 of 1 failed; reproduce with LOOMY_SEED={seed} ");
+    assert!(stderr.contains(&replayed), "{stderr}");
+}
+
+#[test]
+#[should_panic(expected = "the number of iterations must be at least 1")]
+fn zero_iterations_are_rejected() {
+    let mut builder = loomy::model::Builder::new();
+    builder.iterations = 0;
+
+    builder.check(|| panic!("never run"));
+}
+
+#[test]
+fn zero_iterations_from_the_environment_are_rejected() {
+    let output = run_child("failing_model", &[("LOOMY_ITERATIONS", "0")]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(!output.status.success());
+    assert!(
+        stderr.contains("the number of iterations must be at least 1"),
+        "{stderr}"
+    );
+}
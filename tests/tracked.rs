@@ -0,0 +1,153 @@
+//! Bookkeeping of tracked values and allocations under `std`, where threads
+//! only take part in a model when spawned through loomy.
+
+#![cfg(not(any(loom, feature = "shuttle")))]
+
+use std::mem;
+
+use loomy::{thread, Tracked};
+
+#[test]
+fn values_on_other_threads_are_not_tracked() {
+    loomy::model(|| {
+        std::thread::spawn(|| mem::forget(Tracked::new(1)))
+            .join()
+            .unwrap();
+    });
+}
+
+#[test]
+#[should_panic(expected = "1 tracked value(s) leaked")]
+fn values_on_spawned_threads_are_tracked() {
+    loomy::model(|| {
+        thread::spawn(|| mem::forget(Tracked::new(1)))
+            .join()
+            .unwrap();
+    });
+}
+
+#[test]
+#[should_panic(expected = "1 tracked value(s) leaked")]
+fn values_on_scoped_threads_are_tracked() {
+    loomy::model(|| {
+        thread::scope(|s| {
+            s.spawn(|| mem::forget(Tracked::new(1)));
+        });
+    });
+}
+
+#[cfg(feature = "checked")]
+#[test]
+fn allocations_on_other_threads_are_not_tracked() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use loomy::alloc::{alloc, dealloc, Layout};
+
+    static PTR: AtomicUsize = AtomicUsize::new(0);
+
+    loomy::model(|| {
+        // SAFETY: The layout has a non-zero size.
+        let ptr = std::thread::spawn(|| unsafe { alloc(Layout::new::<u64>()) as usize })
+            .join()
+            .unwrap();
+
+        PTR.store(ptr, Ordering::Relaxed);
+    });
+
+    // SAFETY: The pointer was allocated above with this layout.
+    unsafe { dealloc(PTR.load(Ordering::Relaxed) as *mut u8, Layout::new::<u64>()) };
+}
+
+#[cfg(feature = "checked")]
+#[test]
+#[should_panic(expected = "1 allocation(s) leaked")]
+fn allocations_on_spawned_threads_are_tracked() {
+    use loomy::alloc::{alloc, Layout};
+
+    loomy::model(|| {
+        // SAFETY: The layout has a non-zero size.
+        thread::spawn(|| unsafe { alloc(Layout::new::<u64>()) as usize })
+            .join()
+            .unwrap();
+    });
+}
+
+#[cfg(feature = "checked")]
+#[test]
+#[should_panic(expected = "which is not allocated")]
+fn double_deallocations_panic() {
+    use loomy::alloc::{alloc, dealloc, Layout};
+
+    loomy::model(|| {
+        let layout = Layout::new::<u64>();
+
+        // SAFETY: The layout has a non-zero size, and the second deallocation
+        // panics before reaching the allocator.
+        unsafe {
+            let ptr = alloc(layout);
+            dealloc(ptr, layout);
+            dealloc(ptr, layout);
+        }
+    });
+}
+
+#[cfg(feature = "checked")]
+#[test]
+fn deallocations_with_another_layout_panic() {
+    use std::panic;
+
+    use loomy::alloc::{alloc, dealloc, Layout};
+
+    loomy::model(|| {
+        let layout = Layout::new::<u64>();
+
+        // SAFETY: The layout has a non-zero size.
+        let ptr = unsafe { alloc(layout) };
+
+        // SAFETY: The mismatched deallocation panics before reaching the
+        // allocator.
+        let payload =
+            panic::catch_unwind(|| unsafe { dealloc(ptr, Layout::new::<u32>()) }).unwrap_err();
+        let message = payload.downcast_ref::<String>().unwrap();
+
+        assert!(message.contains("with Layout { size: 4"), "{message}");
+        assert!(
+            message.contains("was allocated at tests/tracked.rs"),
+            "{message}"
+        );
+
+        // SAFETY: The pointer is still allocated with this layout.
+        unsafe { dealloc(ptr, layout) };
+    });
+}
+
+#[cfg(feature = "checked")]
+#[test]
+fn reallocations_are_deallocated_with_the_new_layout() {
+    use loomy::alloc::{alloc, dealloc, realloc, Layout};
+
+    loomy::model(|| {
+        let layout = Layout::new::<u64>();
+
+        // SAFETY: The layouts have non-zero sizes, and each pointer is
+        // deallocated with the layout it was last allocated with.
+        unsafe {
+            let ptr = realloc(alloc(layout), layout, 64);
+            dealloc(ptr, Layout::from_size_align(64, layout.align()).unwrap());
+        }
+    });
+}
+
+#[cfg(feature = "checked")]
+#[test]
+#[should_panic(expected = "(64 bytes)")]
+fn reallocations_are_tracked() {
+    use loomy::alloc::{alloc, realloc, Layout};
+
+    loomy::model(|| {
+        let layout = Layout::new::<u64>();
+
+        // SAFETY: The layouts have non-zero sizes.
+        unsafe { realloc(alloc(layout), layout, 64) };
+    });
+}
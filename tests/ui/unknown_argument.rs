@@ -0,0 +1,4 @@
+#[loomy::test(iterations = 10)]
+fn unknown_argument() {}
+
+fn main() {}
@@ -0,0 +1,104 @@
+//! Hang detection with `std`.
+
+#![cfg(not(any(loom, feature = "shuttle")))]
+
+mod common;
+
+use std::panic;
+use std::time::Duration;
+
+use common::{is_child, run_child};
+use loomy::model::Builder;
+use loomy::sync::{Arc, Mutex, RwLock};
+use loomy::thread;
+
+/// Deadlock joining a thread which waits for a lock held by the joiner.
+fn deadlock() {
+    let lock = Arc::new(Mutex::new(()));
+    let _guard = lock.lock().unwrap();
+
+    let lock2 = Arc::clone(&lock);
+    let t = thread::spawn(move || drop(lock2.lock().unwrap()));
+
+    t.join().unwrap();
+}
+
+#[test]
+fn hung_model() {
+    if is_child() {
+        loomy::model(deadlock);
+    }
+}
+
+#[test]
+fn hung_models_fail_their_test() {
+    let output = run_child("hung_model", &[("LOOMY_TIMEOUT", "1")]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(!output.status.success());
+    assert!(stdout.contains("test hung_model ... FAILED"), "{stdout}");
+    assert!(
+        stderr.contains("loomy: execution still running after 1s"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn only_the_hung_execution_is_reported() {
+    static STARTED: std::sync::Barrier = std::sync::Barrier::new(2);
+    static REPORTED: std::sync::Barrier = std::sync::Barrier::new(2);
+
+    // Another watched model, blocked for as long as the hung one runs.
+    let other = std::thread::spawn(|| {
+        let mut builder = Builder::new();
+        builder.timeout = Some(Duration::from_secs(60));
+
+        builder.check(|| {
+            let lock = Arc::new(RwLock::new(()));
+            let guard = lock.write().unwrap();
+
+            let lock2 = Arc::clone(&lock);
+            let t = thread::spawn(move || drop(lock2.read().unwrap()));
+
+            STARTED.wait();
+            REPORTED.wait();
+
+            drop(guard);
+            t.join().unwrap();
+        });
+    });
+
+    STARTED.wait();
+
+    let mut builder = Builder::new();
+    builder.timeout = Some(Duration::from_millis(500));
+
+    let payload = panic::catch_unwind(|| builder.check(deadlock)).unwrap_err();
+    let report = payload.downcast_ref::<String>().unwrap();
+
+    REPORTED.wait();
+    other.join().unwrap();
+
+    let lines = report.lines().collect::<Vec<_>>();
+
+    let blocked = |role: &str, what: &str| {
+        lines
+            .iter()
+            .filter(|line| line.contains(role) && line.contains(&format!(": blocked on {what} at")))
+            .count()
+    };
+
+    assert_eq!(
+        blocked("(running the model)", "JoinHandle::join"),
+        1,
+        "{report}"
+    );
+    assert_eq!(
+        blocked("(spawned at tests/watchdog.rs", "Mutex::lock"),
+        1,
+        "{report}"
+    );
+    assert!(!report.contains("RwLock"), "{report}");
+    assert_eq!(report.matches("(running the model)").count(), 1, "{report}");
+}